@@ -1,7 +1,25 @@
+pub mod alerts;
+pub mod branches;
+pub mod clone;
+pub mod commits;
+pub mod compare;
 pub mod config;
 pub mod copy;
+pub mod discussions;
+pub mod feed;
+pub mod gists;
 pub mod install;
+pub mod issues;
+pub mod notifications;
+pub mod open_reference;
+pub mod projects;
 pub mod pulls;
 pub mod refresh;
+pub mod releases;
+pub mod repo;
 pub mod repos;
+pub mod runs;
+pub mod search_code;
 pub mod search_issues;
+pub mod search_repos;
+pub mod users;
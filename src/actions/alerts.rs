@@ -0,0 +1,352 @@
+use std::str::FromStr;
+
+use eyre::Result;
+use futures::future::join_all;
+use itertools::Itertools;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    alfred::{Icon, Item, Items, Modifier, Modifiers},
+    FullName, OCTOCRAB,
+};
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AlertsConfig {
+    /// repos to check, in addition to every repo of `orgs`
+    repos: Vec<FullName>,
+
+    /// orgs to check, this needs the security manager role or org ownership
+    orgs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl FromStr for Severity {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // code scanning rules without a security severity use error/warning/note
+        match s {
+            "critical" => Ok(Self::Critical),
+            "high" | "error" => Ok(Self::High),
+            "medium" | "moderate" | "warning" => Ok(Self::Medium),
+            "low" | "note" => Ok(Self::Low),
+            _ => Err(eyre::eyre!("unknown severity {s}")),
+        }
+    }
+}
+
+impl Severity {
+    /// Anything unknown is low, rather than hiding the alert.
+    fn parse(s: &str) -> Self {
+        s.parse().unwrap_or(Self::Low)
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Critical => "critical",
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+        }
+    }
+}
+
+/// Any of the three kinds of alert, in the shape the items need.
+#[derive(Debug, PartialEq)]
+struct Alert {
+    repo: String,
+    kind: &'static str,
+    severity: Severity,
+    title: String,
+    package: Option<String>,
+    fixed_in: Option<String>,
+    html_url: String,
+    fix_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertRepository {
+    full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DependabotAlert {
+    html_url: String,
+    /// only in the org listing
+    repository: Option<AlertRepository>,
+    dependency: Dependency,
+    security_advisory: SecurityAdvisory,
+    security_vulnerability: SecurityVulnerability,
+}
+
+#[derive(Debug, Deserialize)]
+struct Dependency {
+    package: Package,
+}
+
+#[derive(Debug, Deserialize)]
+struct Package {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SecurityAdvisory {
+    summary: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SecurityVulnerability {
+    severity: String,
+    first_patched_version: Option<PatchedVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PatchedVersion {
+    identifier: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeScanningAlert {
+    html_url: String,
+    repository: Option<AlertRepository>,
+    rule: Rule,
+    tool: Tool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Rule {
+    description: String,
+    severity: Option<String>,
+    security_severity_level: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tool {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SecretScanningAlert {
+    html_url: String,
+    repository: Option<AlertRepository>,
+    secret_type_display_name: String,
+}
+
+impl Alert {
+    fn from_dependabot(repo: &str, alert: DependabotAlert) -> Self {
+        let repo = alert.repository.map_or(repo.to_string(), |r| r.full_name);
+        let package = alert.dependency.package.name;
+        // dependabot's fix is a pull request that mentions the package
+        let fix_url = format!(
+            "https://github.com/{repo}/pulls?q=is%3Apr+is%3Aopen+author%3Aapp%2Fdependabot+{package}"
+        );
+
+        Self {
+            kind: "dependabot",
+            severity: Severity::parse(&alert.security_vulnerability.severity),
+            title: alert.security_advisory.summary,
+            fixed_in: alert
+                .security_vulnerability
+                .first_patched_version
+                .map(|v| v.identifier),
+            package: Some(package),
+            html_url: alert.html_url,
+            fix_url: Some(fix_url),
+            repo,
+        }
+    }
+
+    fn from_code_scanning(repo: &str, alert: CodeScanningAlert) -> Self {
+        let severity = alert
+            .rule
+            .security_severity_level
+            .or(alert.rule.severity)
+            .unwrap_or_default();
+
+        Self {
+            repo: alert.repository.map_or(repo.to_string(), |r| r.full_name),
+            kind: "code scanning",
+            severity: Severity::parse(&severity),
+            title: alert.rule.description,
+            package: Some(alert.tool.name),
+            fixed_in: None,
+            html_url: alert.html_url,
+            fix_url: None,
+        }
+    }
+
+    fn from_secret_scanning(repo: &str, alert: SecretScanningAlert) -> Self {
+        Self {
+            repo: alert.repository.map_or(repo.to_string(), |r| r.full_name),
+            kind: "secret scanning",
+            // a leaked secret is always worth a look
+            severity: Severity::High,
+            title: format!("Leaked {}", alert.secret_type_display_name),
+            package: None,
+            fixed_in: None,
+            html_url: alert.html_url,
+            fix_url: None,
+        }
+    }
+}
+
+/// Open alerts of the configured repos and orgs, most severe first.
+pub async fn run(config: &AlertsConfig) -> Result<()> {
+    let scopes = config
+        .repos
+        .iter()
+        .map(|repo| (repo.to_string(), format!("/repos/{repo}")))
+        .chain(
+            config
+                .orgs
+                .iter()
+                .map(|org| (org.to_string(), format!("/orgs/{org}"))),
+        )
+        .collect::<Vec<_>>();
+    if scopes.is_empty() {
+        let item = Item::builder()
+            .title("No repos or orgs to check")
+            .subtitle("Add repos or orgs to [alerts] in the config".to_string())
+            .valid(false)
+            .build();
+        println!("{}", serde_json::to_string(&Items::from_iter([item]))?);
+
+        return Ok(());
+    }
+
+    let fetches = scopes.iter().map(|(name, route)| async move {
+        let mut alerts = vec![];
+        for alert in fetch(&format!("{route}/dependabot/alerts"), name).await {
+            alerts.push(Alert::from_dependabot(name, alert));
+        }
+        for alert in fetch(&format!("{route}/code-scanning/alerts"), name).await {
+            alerts.push(Alert::from_code_scanning(name, alert));
+        }
+        for alert in fetch(&format!("{route}/secret-scanning/alerts"), name).await {
+            alerts.push(Alert::from_secret_scanning(name, alert));
+        }
+        alerts
+    });
+    let items: Items = join_all(fetches)
+        .await
+        .into_iter()
+        .flatten()
+        .sorted_by(|a, b| a.severity.cmp(&b.severity).then(a.repo.cmp(&b.repo)))
+        .map(alert_to_item)
+        .collect();
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+/// Open alerts from one endpoint.
+///
+/// Repos without a feature enabled, or without access to it, answer with an
+/// error, which shouldn't hide the alerts of everything else.
+async fn fetch<T: DeserializeOwned>(route: &str, name: &str) -> Vec<T> {
+    let params = json!({ "state": "open", "per_page": 100 });
+    match OCTOCRAB.get(route, Some(&params)).await {
+        Ok(alerts) => alerts,
+        Err(err) => {
+            eprintln!("skipping {route} for {name}: {err}");
+            vec![]
+        }
+    }
+}
+
+fn alert_to_item(alert: Alert) -> Item {
+    let mut subtitle = format!(
+        "{} · {} · {}",
+        alert.repo,
+        alert.kind,
+        alert.severity.as_str()
+    );
+    if let Some(package) = &alert.package {
+        subtitle.push_str(&format!(" · {package}"));
+    }
+    if let Some(fixed_in) = &alert.fixed_in {
+        subtitle.push_str(&format!(" · fixed in {fixed_in}"));
+    }
+    let fix = match &alert.fix_url {
+        Some(fix_url) => Modifier::builder()
+            .subtitle("Open the fix pull request")
+            .arg(fix_url)
+            .build(),
+        None => Modifier::builder()
+            .subtitle("No fix pull request for this kind of alert")
+            .valid(false)
+            .build(),
+    };
+
+    Item::builder()
+        .title(alert.title)
+        .subtitle(subtitle)
+        .uid(&alert.html_url)
+        .arg(&alert.html_url)
+        .icon(Icon::bundled(&format!(
+            "severity-{}",
+            alert.severity.as_str()
+        )))
+        .variables(json!({
+            "full_name": alert.repo,
+            "html_url": alert.html_url,
+        }))
+        .mods(Modifiers::builder().cmd(fix).build())
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config() {
+        let config: AlertsConfig = toml::from_str(r#"orgs = ["mozilla"]"#).unwrap();
+        assert_eq!(config.orgs, vec!["mozilla".to_string()]);
+        assert!(config.repos.is_empty());
+    }
+
+    #[test]
+    fn test_severity() {
+        assert_eq!("moderate".parse::<Severity>().unwrap(), Severity::Medium);
+        assert_eq!("error".parse::<Severity>().unwrap(), Severity::High);
+        assert!("".parse::<Severity>().is_err());
+        assert_eq!(Severity::parse(""), Severity::Low);
+        assert!(Severity::Critical < Severity::Low);
+    }
+
+    #[test]
+    fn test_dependabot_alert() {
+        let alert: DependabotAlert = serde_json::from_str(
+            r#"{
+                "number": 2,
+                "state": "open",
+                "html_url": "https://github.com/a/b/security/dependabot/2",
+                "dependency": { "package": { "ecosystem": "cargo", "name": "rustls" } },
+                "security_advisory": { "summary": "rustls busy loop", "severity": "high" },
+                "security_vulnerability": {
+                    "severity": "high",
+                    "first_patched_version": { "identifier": "0.21.11" }
+                }
+            }"#,
+        )
+        .unwrap();
+        let alert = Alert::from_dependabot("a/b", alert);
+        assert_eq!(alert.repo, "a/b");
+        assert_eq!(alert.severity, Severity::High);
+        assert_eq!(alert.package.as_deref(), Some("rustls"));
+        assert_eq!(alert.fixed_in.as_deref(), Some("0.21.11"));
+        assert!(alert.fix_url.unwrap().ends_with("dependabot+rustls"));
+    }
+}
@@ -0,0 +1,136 @@
+use eyre::{ContextCompat, Result};
+use serde_json::json;
+
+use crate::{
+    alfred::{Icon, Item, Items, Modifier, Modifiers},
+    github_util,
+    graphql::{
+        self,
+        branches::{self, Branches, Ref},
+    },
+    FullName, OCTOCRAB,
+};
+
+/// Branches of a repo, the default branch first, then the most recently committed to.
+pub async fn run(repo: FullName) -> Result<()> {
+    let default_branch = OCTOCRAB
+        .repos(&repo.owner, &repo.name)
+        .get()
+        .await?
+        .default_branch
+        .wrap_err("default_branch is None")?;
+    let mut variables = branches::Variables {
+        owner: repo.owner.clone(),
+        name: repo.name.clone(),
+        base: default_branch.clone(),
+        after: None,
+    };
+    let mut refs = vec![];
+    let url = loop {
+        let r = graphql::query::<Branches>(&variables)
+            .await?
+            .repository
+            .wrap_err(format!("{repo} not found"))?;
+        variables.after = r.refs.next_cursor();
+        refs.extend(r.refs.nodes);
+        if variables.after.is_none() {
+            break r.url;
+        }
+    };
+    let (default, rest): (Vec<_>, Vec<_>) = refs
+        .into_iter()
+        .partition(|branch| branch.name == default_branch);
+    let items: Items = default
+        .into_iter()
+        .chain(rest)
+        .map(|branch| branch_to_item(&repo, &url, &default_branch, branch))
+        .collect();
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+fn branch_to_item(repo: &FullName, url: &str, default_branch: &str, branch: Ref) -> Item {
+    let is_default = branch.name == default_branch;
+    let mut about = vec![];
+    if is_default {
+        about.push("default".to_string());
+    } else if let Some(compare) = &branch.compare {
+        // compare goes from the branch to the default branch, see branches.graphql
+        about.push(format!(
+            "{} ahead, {} behind {default_branch}",
+            compare.behind_by, compare.ahead_by
+        ));
+    }
+    if let Some(rule) = &branch.ref_update_rule {
+        about.push(format!("protected by {}", rule.pattern));
+    }
+    if let Some(date) = branch.target.as_ref().and_then(|t| t.committed_date) {
+        about.push(format!("committed {}", date.format("%Y-%m-%d")));
+    }
+    let full_name = format!("{repo}@{}", branch.name);
+    let tree_url = format!("{url}/tree/{}", github_util::encode_ref(&branch.name));
+    let compare_url = format!(
+        "{url}/compare/{}...{}",
+        github_util::encode_ref(default_branch),
+        github_util::encode_ref(&branch.name)
+    );
+
+    let compare = match is_default {
+        true => Modifier::builder()
+            .subtitle("This is the default branch")
+            .valid(false)
+            .build(),
+        false => Modifier::builder()
+            .subtitle(format!("Compare with {default_branch}"))
+            .arg(&compare_url)
+            .variables(json!({
+                "action": "compare",
+                "repo": full_name,
+            }))
+            .build(),
+    };
+    let pull = match (is_default, branch.associated_pull_requests.nodes.first()) {
+        (true, _) => Modifier::builder()
+            .subtitle("This is the default branch")
+            .valid(false)
+            .build(),
+        (false, Some(pull)) => Modifier::builder()
+            .subtitle(format!("Open pull request #{}", pull.number))
+            .arg(&pull.url)
+            .build(),
+        (false, None) => Modifier::builder()
+            .subtitle("Open a pull request")
+            .arg(format!("{compare_url}?expand=1"))
+            .build(),
+    };
+
+    Item::builder()
+        .title(&branch.name)
+        .subtitle(about.join(" · "))
+        .uid(&full_name)
+        .arg(&tree_url)
+        .icon(Icon::bundled("branch"))
+        .variables(json!({
+            "full_name": full_name,
+            "html_url": tree_url,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(compare)
+                .alt(pull)
+                .ctrl(
+                    Modifier::builder()
+                        .subtitle("List commits")
+                        .variables(json!({
+                            "action": "commits",
+                            "repo": full_name,
+                        }))
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
@@ -0,0 +1,252 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Stdio,
+    str::FromStr,
+};
+
+use eyre::{ContextCompat, Result};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::{hub_compat::HubConfig, template, FullName};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloneConfig {
+    /// https or ssh, when unset the protocol from the hub config is used
+    protocol: Option<Protocol>,
+
+    /// where to put clones, {owner} and {name} are replaced
+    directory: String,
+
+    /// shell command used to open the clone, e.g. "code"
+    editor: Option<String>,
+}
+
+impl Default for CloneConfig {
+    fn default() -> Self {
+        Self {
+            protocol: None,
+            directory: "~/src/{owner}/{name}".to_string(),
+            editor: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    #[default]
+    Https,
+    Ssh,
+}
+
+impl FromStr for Protocol {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "https" => Ok(Self::Https),
+            // hub calls ssh "git"
+            "ssh" | "git" => Ok(Self::Ssh),
+            _ => Err(eyre::eyre!("unknown protocol {s}")),
+        }
+    }
+}
+
+impl Protocol {
+    pub fn url(&self, repo: &FullName) -> String {
+        match self {
+            Self::Https => format!("https://github.com/{repo}.git"),
+            Self::Ssh => format!("git@github.com:{repo}.git"),
+        }
+    }
+}
+
+impl CloneConfig {
+    pub fn protocol(&self) -> Result<Protocol> {
+        if let Some(protocol) = self.protocol {
+            return Ok(protocol);
+        }
+        match HubConfig::new().ok().and_then(|hc| hc.protocol) {
+            Some(protocol) => protocol.parse(),
+            None => Ok(Protocol::default()),
+        }
+    }
+
+    pub fn path(&self, repo: &FullName) -> Result<PathBuf> {
+        let directory = template::render(&self.directory, |name| match name {
+            "owner" => Some(repo.owner.clone()),
+            "name" => Some(repo.name.clone()),
+            _ => None,
+        })?;
+        match directory.strip_prefix("~/") {
+            Some(rest) => Ok(dirs::home_dir().wrap_err("missing $HOME")?.join(rest)),
+            None => Ok(PathBuf::from(directory)),
+        }
+    }
+}
+
+/// Clone the repo (unless it already exists) and open it in the editor.
+///
+/// `url` overrides the clone url, which is mostly useful for file:// urls.
+pub async fn run(config: &CloneConfig, repo: FullName, url: Option<String>) -> Result<()> {
+    let url = match url {
+        Some(url) => url,
+        None => config.protocol()?.url(&repo),
+    };
+    let path = config.path(&repo)?;
+    clone(&url, &path).await?;
+    open_in_editor(config, &path).await?;
+    println!("{}", path.to_string_lossy());
+
+    Ok(())
+}
+
+/// Open an existing clone in the editor, without cloning it first.
+pub async fn open_local(config: &CloneConfig, repo: FullName) -> Result<()> {
+    let path = config.path(&repo)?;
+    if !path.exists() {
+        return Err(eyre::eyre!(
+            "{repo} isn't cloned to {}",
+            path.to_string_lossy()
+        ));
+    }
+    open_in_editor(config, &path).await?;
+    println!("{}", path.to_string_lossy());
+
+    Ok(())
+}
+
+/// The branch checked out in the local clone of a repo.
+pub async fn current_branch(config: &CloneConfig, repo: &FullName) -> Result<String> {
+    let path = config.path(repo)?;
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&path)
+        .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(eyre::eyre!(
+            "no branch checked out in {}",
+            path.to_string_lossy()
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Returns false if the path already exists and nothing was cloned.
+async fn clone(url: &str, path: &Path) -> Result<bool> {
+    if path.exists() {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let status = Command::new("git")
+        .arg("clone")
+        .arg("--quiet")
+        .arg(url)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .status()
+        .await?;
+    if !status.success() {
+        return Err(eyre::eyre!("git clone {url} failed with {status}"));
+    }
+
+    Ok(true)
+}
+
+async fn open_in_editor(config: &CloneConfig, path: &Path) -> Result<()> {
+    let Some(editor) = &config.editor else {
+        open::that(path)?;
+        return Ok(());
+    };
+    let sh = env::var("SHELL")
+        .ok()
+        .unwrap_or_else(|| "/bin/sh".to_string());
+    Command::new(sh)
+        .arg("-c")
+        .arg(format!(r#"{editor} "$clone_path""#))
+        .env("clone_path", path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .spawn()?
+        .wait()
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let config = CloneConfig {
+            directory: "/src/{owner}/{name}".to_string(),
+            ..Default::default()
+        };
+        let repo = "dylanwh/alfred-workflow-github".parse().unwrap();
+        assert_eq!(
+            config.path(&repo).unwrap(),
+            PathBuf::from("/src/dylanwh/alfred-workflow-github")
+        );
+    }
+
+    #[test]
+    fn test_partial_config() {
+        let config: CloneConfig = toml::from_str(r#"editor = "code""#).unwrap();
+        assert_eq!(config.editor.as_deref(), Some("code"));
+        assert_eq!(config.directory, "~/src/{owner}/{name}");
+    }
+
+    #[tokio::test]
+    async fn test_clone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bare = tmp.path().join("bare.git");
+        let status = std::process::Command::new("git")
+            .args(["init", "--quiet", "--bare"])
+            .arg(&bare)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let url = format!("file://{}", bare.to_string_lossy());
+        let dest = tmp.path().join("src/owner/name");
+        assert!(clone(&url, &dest).await.unwrap());
+        assert!(dest.join(".git").exists());
+
+        // second time around the existing clone is left alone
+        assert!(!clone(&url, &dest).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_current_branch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = CloneConfig {
+            directory: format!("{}/{{owner}}/{{name}}", tmp.path().to_string_lossy()),
+            ..Default::default()
+        };
+        let repo = "owner/name".parse().unwrap();
+        let path = config.path(&repo).unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "--quiet", "--initial-branch", "feature/x"])
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        assert_eq!(current_branch(&config, &repo).await.unwrap(), "feature/x");
+    }
+}
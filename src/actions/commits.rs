@@ -0,0 +1,79 @@
+use eyre::Result;
+use octocrab::models::repos::RepoCommit;
+use serde_json::json;
+
+use crate::{
+    alfred::{AuthorIcon, Item, Items, Modifier, Modifiers},
+    github_util,
+    reference::RepoBranch,
+    FullName, OCTOCRAB,
+};
+
+/// Recent commits of a branch, or of the default branch when none is given.
+pub async fn run(repo: RepoBranch) -> Result<()> {
+    let RepoBranch { full_name, branch } = repo;
+    let repos = OCTOCRAB.repos(&full_name.owner, &full_name.name);
+    let mut commits = repos.list_commits().per_page(20u8);
+    if let Some(branch) = branch {
+        commits = commits.branch(branch);
+    }
+    let items: Items = commits
+        .send()
+        .await?
+        .into_iter()
+        .map(|commit| commit_to_item(&full_name, commit))
+        .collect();
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+pub fn commit_to_item(repo: &FullName, commit: RepoCommit) -> Item {
+    let short_sha = &commit.sha[..7];
+    let full_name = format!("{repo}@{short_sha}");
+    let summary = commit.commit.message.lines().next().unwrap_or_default();
+    let login = commit.author.as_ref().map(|a| a.login.clone());
+    // commits by emails that aren't linked to an account only have a git author
+    let author = login
+        .clone()
+        .or_else(|| commit.commit.author.as_ref().map(|a| a.user.name.clone()));
+    let committed_at = commit.commit.committer.as_ref().and_then(|c| c.date);
+
+    Item::builder()
+        .title(summary)
+        .subtitle(format!(
+            "{full_name} committed by {author}",
+            author = author.unwrap_or_default()
+        ))
+        .uid(&commit.sha)
+        .arg(&commit.html_url)
+        .icon(AuthorIcon::from(commit.author))
+        .variables(json!({
+            "created_at": committed_at,
+            "full_name": full_name,
+            "html_url": commit.html_url,
+            "owner": login,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(
+                    Modifier::builder()
+                        .subtitle(format!("Copy {}", commit.sha))
+                        .arg(&commit.sha)
+                        .variables(json!({ "action": "clipboard" }))
+                        .build(),
+                )
+                .alt(
+                    Modifier::builder()
+                        .subtitle("Open the diff")
+                        .arg(format!("{}.diff", commit.html_url))
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
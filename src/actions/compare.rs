@@ -0,0 +1,270 @@
+use eyre::{ContextCompat, Result};
+use octocrab::{
+    models::{pulls::PullRequest, repos::RepoCommit},
+    params,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    actions::{
+        clone::{current_branch, CloneConfig},
+        commits::commit_to_item,
+        pulls::pull_to_item,
+    },
+    alfred::{
+        models::{AlfredWorkflow, Output},
+        Icon, Item, Items, Modifier, Modifiers,
+    },
+    github_util,
+    reference::RepoBranch,
+    FullName, OCTOCRAB,
+};
+
+/// The parts of GET /repos/{owner}/{name}/compare/{base}...{head} that are shown.
+#[derive(Debug, Deserialize)]
+struct Comparison {
+    ahead_by: u64,
+    behind_by: u64,
+    html_url: String,
+    /// oldest first, at most 250
+    commits: Vec<RepoCommit>,
+    #[serde(default)]
+    files: Vec<serde_json::Value>,
+}
+
+/// Where a branch stands against the default branch, and its pull request if there is one.
+///
+/// Without a branch, the one checked out in the local clone is used.
+pub async fn run(config: &CloneConfig, repo: RepoBranch) -> Result<()> {
+    let (repo, head) = resolve(config, repo).await?;
+    let base = default_branch(&repo).await?;
+    let comparison = compare(&repo, &base, &head).await?;
+    let pull = open_pull(&repo, &head).await?;
+
+    let mut items = vec![summary_item(
+        &repo,
+        &base,
+        &head,
+        &comparison,
+        pull.is_some(),
+    )];
+    if let Some(pull) = pull {
+        items.push(pull_to_item(&repo, pull)?);
+    }
+    items.extend(
+        comparison
+            .commits
+            .into_iter()
+            .rev()
+            .map(|commit| commit_to_item(&repo, commit)),
+    );
+    let items = Items::from_iter(items);
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+/// Open a pull request from the branch into the default branch, with the
+/// title and body taken from its commits.
+pub async fn create_pull(config: &CloneConfig, repo: RepoBranch) -> Result<()> {
+    let (repo, head) = resolve(config, repo).await?;
+    let full_name = format!("{repo}@{head}");
+    let (result, message, html_url) = match create(&repo, &head).await {
+        Ok(pull) => (
+            "success",
+            format!("{repo}#{} opened from {head}", pull.number),
+            pull.html_url.map(|url| url.to_string()),
+        ),
+        Err(err) => ("failure", format!("{full_name}: {err}"), None),
+    };
+    let output: Output = AlfredWorkflow::builder()
+        .arg(&message)
+        .variables(json!({
+            "result": result,
+            "message": message,
+            "full_name": full_name,
+            "html_url": html_url,
+        }))
+        .build();
+    let json = serde_json::to_string(&output)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+async fn create(repo: &FullName, head: &str) -> Result<PullRequest> {
+    let base = default_branch(repo).await?;
+    let comparison = compare(repo, &base, head).await?;
+    if comparison.ahead_by == 0 {
+        return Err(eyre::eyre!("{head} has no commits that {base} doesn't"));
+    }
+    let messages = comparison
+        .commits
+        .iter()
+        .map(|commit| commit.commit.message.as_str())
+        .collect::<Vec<_>>();
+    let (title, body) = pull_text(&messages);
+    let pull = OCTOCRAB
+        .pulls(&repo.owner, &repo.name)
+        .create(title, head, base)
+        .body(body)
+        .send()
+        .await?;
+
+    Ok(pull)
+}
+
+async fn resolve(config: &CloneConfig, repo: RepoBranch) -> Result<(FullName, String)> {
+    let head = match repo.branch {
+        Some(branch) => branch,
+        None => current_branch(config, &repo.full_name).await?,
+    };
+
+    Ok((repo.full_name, head))
+}
+
+async fn default_branch(repo: &FullName) -> Result<String> {
+    OCTOCRAB
+        .repos(&repo.owner, &repo.name)
+        .get()
+        .await?
+        .default_branch
+        .wrap_err("default_branch is None")
+}
+
+async fn compare(repo: &FullName, base: &str, head: &str) -> Result<Comparison> {
+    let route = format!(
+        "/repos/{repo}/compare/{}...{}",
+        github_util::encode_ref(base),
+        github_util::encode_ref(head)
+    );
+
+    Ok(OCTOCRAB.get(route, None::<&()>).await?)
+}
+
+async fn open_pull(repo: &FullName, head: &str) -> Result<Option<PullRequest>> {
+    let pulls = OCTOCRAB
+        .pulls(&repo.owner, &repo.name)
+        .list()
+        .state(params::State::Open)
+        .head(format!("{}:{head}", repo.owner))
+        .per_page(1u8)
+        .send()
+        .await?;
+
+    Ok(pulls.into_iter().next())
+}
+
+/// A title and body for a pull request from the messages of its commits,
+/// oldest first.
+///
+/// A single commit gives its summary and the rest of its message, like github does,
+/// several give the first summary and a list of all of them.
+fn pull_text(messages: &[&str]) -> (String, String) {
+    let summary = |message: &str| {
+        message
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    match messages {
+        [message] => {
+            let (_, rest) = message.split_once('\n').unwrap_or_default();
+            (summary(message), rest.trim().to_string())
+        }
+        [first, ..] => {
+            let body = messages
+                .iter()
+                .map(|message| format!("- {}", summary(message)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            (summary(first), body)
+        }
+        [] => (String::new(), String::new()),
+    }
+}
+
+fn summary_item(
+    repo: &FullName,
+    base: &str,
+    head: &str,
+    comparison: &Comparison,
+    has_pull: bool,
+) -> Item {
+    let messages = comparison
+        .commits
+        .iter()
+        .map(|commit| commit.commit.message.as_str())
+        .collect::<Vec<_>>();
+    let (title, _) = pull_text(&messages);
+    let create = if has_pull {
+        Modifier::builder()
+            .subtitle("A pull request is already open")
+            .valid(false)
+            .build()
+    } else if comparison.ahead_by == 0 {
+        Modifier::builder()
+            .subtitle(format!("Nothing to merge into {base}"))
+            .valid(false)
+            .build()
+    } else {
+        Modifier::builder()
+            .subtitle(format!("Create pull request “{title}”"))
+            .variables(json!({
+                "action": "create-pull",
+                "repo": format!("{repo}@{head}"),
+            }))
+            .build()
+    };
+
+    Item::builder()
+        .title(format!("{head} → {base}"))
+        .subtitle(format!(
+            "{} commits ahead, {} behind · {} files changed",
+            comparison.ahead_by,
+            comparison.behind_by,
+            comparison.files.len()
+        ))
+        .arg(&comparison.html_url)
+        .icon(Icon::bundled("branch"))
+        .variables(json!({
+            "full_name": format!("{repo}@{head}"),
+            "html_url": &comparison.html_url,
+        }))
+        .mods(Modifiers::builder().cmd(create).build())
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_text() {
+        assert_eq!(
+            pull_text(&["Add compare action\n\nShows commits ahead.\n"]),
+            (
+                "Add compare action".to_string(),
+                "Shows commits ahead.".to_string()
+            )
+        );
+        assert_eq!(
+            pull_text(&["Fix typo"]),
+            ("Fix typo".to_string(), String::new())
+        );
+        assert_eq!(
+            pull_text(&["Add compare action\n\nbody", "Fix typo"]),
+            (
+                "Add compare action".to_string(),
+                "- Add compare action\n- Fix typo".to_string()
+            )
+        );
+    }
+}
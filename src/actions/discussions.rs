@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use eyre::Result;
+use serde_json::json;
+
+use crate::{
+    alfred::{AuthorIcon, Icon, Item, Items, Modifier, Modifiers},
+    github_util,
+    graphql::{
+        self,
+        discussions::{self, Discussion, Discussions},
+    },
+    FullName,
+};
+
+/// Recently updated discussions of a repo, or those matching `query`.
+pub async fn run(repo: FullName, query: Option<String>) -> Result<()> {
+    let query = query.unwrap_or_default();
+    let variables = discussions::Variables {
+        owner: repo.owner.clone(),
+        name: repo.name.clone(),
+        query: format!("repo:{repo} {query}"),
+        search: !query.trim().is_empty(),
+    };
+    let data = graphql::query::<Discussions>(&variables).await?;
+    let discussions = match (data.repository, data.search) {
+        (Some(repository), _) => repository.discussions.nodes,
+        (_, Some(search)) => search.nodes,
+        _ => vec![],
+    };
+    let items: Items = discussions
+        .into_iter()
+        .map(|discussion| discussion_to_item(&repo, discussion))
+        .collect::<Result<_>>()?;
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+fn discussion_to_item(repo: &FullName, discussion: Discussion) -> Result<Item> {
+    let full_name = format!("{repo}#{number}", number = discussion.number);
+    let login = discussion.author.map(|author| author.login);
+    let mut subtitle = format!(
+        "#{number} in {category}",
+        number = discussion.number,
+        category = discussion.category.name
+    );
+    if discussion.is_answered == Some(true) {
+        subtitle.push_str(" · answered");
+    }
+    match discussion.comments.total_count {
+        0 => {}
+        1 => subtitle.push_str(" · 1 comment"),
+        n => subtitle.push_str(&format!(" · {n} comments")),
+    }
+    if let Some(login) = &login {
+        subtitle.push_str(&format!(" · by {login}"));
+    }
+    let icon = match &login {
+        Some(login) => AuthorIcon::from_str(login)?.into(),
+        None => Icon::bundled("discussion"),
+    };
+
+    let item = Item::builder()
+        .title(discussion.title)
+        .subtitle(subtitle)
+        .uid(&discussion.url)
+        .arg(&discussion.url)
+        .icon(icon)
+        .variables(json!({
+            "created_at": discussion.created_at,
+            "updated_at": discussion.updated_at,
+            "full_name": &full_name,
+            "html_url": &discussion.url,
+            "owner": login,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(
+                    Modifier::builder()
+                        .subtitle("Copy markdown link")
+                        .variables(json!({
+                            "action": "copy",
+                            "full_name": &full_name,
+                            "html_url": &discussion.url,
+                        }))
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    Ok(item)
+}
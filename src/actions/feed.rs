@@ -0,0 +1,257 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    alfred::{AuthorIcon, Item, Items, ALFRED_WORKFLOW_CACHE},
+    cache, github_util,
+    hub_compat::HubConfig,
+    OCTOCRAB,
+};
+
+/// The feed changes quickly, but opening alfred twice in a row shouldn't wait twice.
+const EXPIRES: Duration = Duration::minutes(5);
+
+/// The parts of an event from the events api that go into an item,
+/// the payload differs for each type.
+#[derive(Debug, Serialize, Deserialize)]
+struct Event {
+    id: String,
+    r#type: String,
+    actor: Actor,
+    repo: EventRepo,
+    #[serde(default)]
+    payload: Value,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Actor {
+    login: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EventRepo {
+    name: String,
+}
+
+/// Events received by the user, from the people they follow and the repos they watch,
+/// like the github dashboard.
+pub async fn run(no_cache: bool) -> Result<()> {
+    let file = ALFRED_WORKFLOW_CACHE.as_ref()?.join("feed.json");
+    let expires = if no_cache { Duration::zero() } else { EXPIRES };
+    let events: Vec<Event> = cache::cached(&file, expires, received_events).await?;
+    let items: Items = events.into_iter().filter_map(event_to_item).collect();
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+async fn received_events() -> Result<Vec<Event>> {
+    let login = HubConfig::new()?.user;
+    let route = format!("/users/{login}/received_events");
+
+    Ok(OCTOCRAB
+        .get(route, Some(&json!({ "per_page": 50 })))
+        .await?)
+}
+
+/// A readable title and a url for an event, None for the kinds that aren't worth showing.
+fn describe(event: &Event) -> Option<(String, String)> {
+    let actor = &event.actor.login;
+    let repo = &event.repo.name;
+    let payload = &event.payload;
+    let repo_url = format!("https://github.com/{repo}");
+    let str = |pointer: &str| payload.pointer(pointer).and_then(Value::as_str);
+
+    let described = match event.r#type.as_str() {
+        "PushEvent" => {
+            let branch = str("/ref")?.trim_start_matches("refs/heads/");
+            let commits = match payload["size"].as_u64() {
+                Some(1) => "1 commit".to_string(),
+                Some(size) => format!("{size} commits"),
+                None => "commits".to_string(),
+            };
+            let url = match (str("/before"), str("/head")) {
+                (Some(before), Some(head)) => format!("{repo_url}/compare/{before}...{head}"),
+                _ => format!("{repo_url}/tree/{branch}"),
+            };
+            (
+                format!("{actor} pushed {commits} to {branch} in {repo}"),
+                url,
+            )
+        }
+        "PullRequestEvent" => {
+            let action = match str("/action")? {
+                "closed" if payload["pull_request"]["merged"] == true => "merged",
+                action => action,
+            };
+            if !matches!(action, "opened" | "merged" | "reopened" | "closed") {
+                return None;
+            }
+            (
+                format!(
+                    "{actor} {action} pull request {repo}#{}: {}",
+                    payload["number"],
+                    str("/pull_request/title").unwrap_or_default()
+                ),
+                str("/pull_request/html_url")?.to_string(),
+            )
+        }
+        "IssuesEvent" => {
+            let action = str("/action")?;
+            if !matches!(action, "opened" | "closed" | "reopened") {
+                return None;
+            }
+            (
+                format!(
+                    "{actor} {action} issue {repo}#{}: {}",
+                    payload["issue"]["number"],
+                    str("/issue/title").unwrap_or_default()
+                ),
+                str("/issue/html_url")?.to_string(),
+            )
+        }
+        "IssueCommentEvent" => (
+            format!(
+                "{actor} commented on {repo}#{}: {}",
+                payload["issue"]["number"],
+                str("/issue/title").unwrap_or_default()
+            ),
+            str("/comment/html_url")?.to_string(),
+        ),
+        "ReleaseEvent" => {
+            let name = str("/release/name")
+                .filter(|name| !name.is_empty())
+                .or(str("/release/tag_name"))?;
+            (
+                format!("{actor} released {name} of {repo}"),
+                str("/release/html_url")?.to_string(),
+            )
+        }
+        "WatchEvent" => (format!("{actor} starred {repo}"), repo_url),
+        "ForkEvent" => (
+            format!(
+                "{actor} forked {repo} to {}",
+                str("/forkee/full_name").unwrap_or_default()
+            ),
+            str("/forkee/html_url")?.to_string(),
+        ),
+        "CreateEvent" => match (str("/ref_type")?, str("/ref")) {
+            ("repository", _) => (format!("{actor} created {repo}"), repo_url),
+            ("tag", Some(tag)) => (
+                format!("{actor} tagged {tag} in {repo}"),
+                format!("{repo_url}/releases/tag/{tag}"),
+            ),
+            (ref_type, Some(name)) => (
+                format!("{actor} created {ref_type} {name} in {repo}"),
+                format!("{repo_url}/tree/{name}"),
+            ),
+            _ => return None,
+        },
+        "PublicEvent" => (format!("{actor} made {repo} public"), repo_url),
+        "MemberEvent" => (
+            format!(
+                "{actor} added {} to {repo}",
+                str("/member/login").unwrap_or_default()
+            ),
+            repo_url,
+        ),
+        _ => return None,
+    };
+
+    Some(described)
+}
+
+fn event_to_item(event: Event) -> Option<Item> {
+    let (title, url) = describe(&event)?;
+
+    Some(
+        Item::builder()
+            .title(title)
+            .subtitle(format!(
+                "{} · {}",
+                event.repo.name,
+                event.created_at.format("%Y-%m-%d %H:%M")
+            ))
+            .uid(&event.id)
+            .arg(&url)
+            .icon(AuthorIcon::from_str(&event.actor.login).ok()?)
+            .variables(json!({
+                "created_at": event.created_at,
+                "full_name": event.repo.name,
+                "html_url": url,
+                "owner": event.actor.login,
+            }))
+            .build(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(r#type: &str, payload: Value) -> Event {
+        serde_json::from_value(json!({
+            "id": "1",
+            "type": r#type,
+            "actor": { "login": "octocat", "avatar_url": "https://avatars.githubusercontent.com/u/583231" },
+            "repo": { "name": "a/b" },
+            "payload": payload,
+            "created_at": "2023-10-01T12:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_describe() {
+        let push = event(
+            "PushEvent",
+            json!({ "ref": "refs/heads/main", "size": 2, "before": "aaa", "head": "bbb" }),
+        );
+        assert_eq!(
+            describe(&push),
+            Some((
+                "octocat pushed 2 commits to main in a/b".to_string(),
+                "https://github.com/a/b/compare/aaa...bbb".to_string()
+            ))
+        );
+
+        let merged = event(
+            "PullRequestEvent",
+            json!({
+                "action": "closed",
+                "number": 3,
+                "pull_request": {
+                    "title": "Add feed",
+                    "merged": true,
+                    "html_url": "https://github.com/a/b/pull/3"
+                }
+            }),
+        );
+        assert_eq!(
+            describe(&merged).unwrap().0,
+            "octocat merged pull request a/b#3: Add feed"
+        );
+
+        let star = event("WatchEvent", json!({ "action": "started" }));
+        assert_eq!(
+            describe(&star),
+            Some((
+                "octocat starred a/b".to_string(),
+                "https://github.com/a/b".to_string()
+            ))
+        );
+
+        let labeled = event("PullRequestEvent", json!({ "action": "labeled" }));
+        assert_eq!(describe(&labeled), None);
+        assert_eq!(describe(&event("GollumEvent", json!({}))), None);
+    }
+}
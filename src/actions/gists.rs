@@ -0,0 +1,207 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Utc};
+use eyre::{ContextCompat, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::{fs, process::Command};
+
+use crate::{
+    alfred::{
+        AlfredWorkflow, Icon, Item, Items, Modifier, Modifiers, Output, ALFRED_WORKFLOW_CACHE,
+    },
+    cache, OCTOCRAB,
+};
+
+/// Every keystroke reruns the filter, and listing all the gists takes a request per page.
+const EXPIRES: Duration = Duration::minutes(5);
+
+/// The parts of a gist that go into an item, octocrab's gist can't be cached.
+#[derive(Debug, Serialize, Deserialize)]
+struct Gist {
+    id: String,
+    description: Option<String>,
+    files: Vec<String>,
+    html_url: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<octocrab::models::gists::Gist> for Gist {
+    fn from(gist: octocrab::models::gists::Gist) -> Self {
+        Self {
+            id: gist.id,
+            description: gist.description,
+            files: gist.files.into_keys().collect(),
+            html_url: gist.html_url.to_string(),
+            created_at: gist.created_at,
+            updated_at: gist.updated_at,
+        }
+    }
+}
+
+/// The user's gists whose description or file names contain every word of `query`.
+pub async fn run(query: Option<String>) -> Result<()> {
+    let file = ALFRED_WORKFLOW_CACHE.as_ref()?.join("gists.json");
+    let gists: Vec<Gist> = cache::cached(&file, EXPIRES, list_gists).await?;
+    let query = query.unwrap_or_default();
+    let items: Items = gists
+        .iter()
+        .filter(|gist| gist_matches(gist.description.as_deref(), gist.files.iter(), &query))
+        .map(gist_to_item)
+        .collect();
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+async fn list_gists() -> Result<Vec<Gist>> {
+    let octocrab = OCTOCRAB.clone();
+    let page = octocrab
+        .gists()
+        .list_all_gists()
+        .per_page(100)
+        .send()
+        .await?;
+    let gists = octocrab.all_pages(page).await?;
+
+    Ok(gists.into_iter().map(Gist::from).collect())
+}
+
+fn gist_matches<'a>(
+    description: Option<&'a str>,
+    files: impl Iterator<Item = &'a String>,
+    query: &str,
+) -> bool {
+    let haystack = files
+        .map(String::as_str)
+        .chain(description)
+        .join(" ")
+        .to_lowercase();
+
+    query
+        .to_lowercase()
+        .split_whitespace()
+        .all(|word| haystack.contains(word))
+}
+
+fn gist_to_item(gist: &Gist) -> Item {
+    let files = gist.files.join(", ");
+    let html_url = gist.html_url.clone();
+    let title = match gist.description.as_deref() {
+        Some(description) if !description.is_empty() => description.to_string(),
+        _ => files.clone(),
+    };
+
+    Item::builder()
+        .title(title)
+        .subtitle(format!(
+            "{files} · updated {}",
+            gist.updated_at.format("%Y-%m-%d")
+        ))
+        .uid(&gist.id)
+        .arg(&html_url)
+        .icon(Icon::bundled("gist"))
+        .variables(json!({
+            "created_at": gist.created_at,
+            "updated_at": gist.updated_at,
+            "full_name": format!("gist {}", gist.id),
+            "html_url": html_url,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(
+                    Modifier::builder()
+                        .subtitle("Copy url")
+                        .arg(&html_url)
+                        .variables(json!({ "action": "clipboard" }))
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+/// Create a gist from `files`, or from the clipboard when there are none,
+/// and output its url as the arg.
+pub async fn create(
+    public: bool,
+    description: Option<String>,
+    filename: String,
+    files: Vec<PathBuf>,
+) -> Result<()> {
+    let mut contents = vec![];
+    for path in &files {
+        let name = path
+            .file_name()
+            .wrap_err(format!("{path:?} is not a file"))?
+            .to_string_lossy()
+            .to_string();
+        contents.push((name, fs::read_to_string(path).await?));
+    }
+    if contents.is_empty() {
+        contents.push((filename, clipboard().await?));
+    }
+    if contents
+        .iter()
+        .all(|(_, content)| content.trim().is_empty())
+    {
+        return Err(eyre::eyre!("nothing to put in the gist"));
+    }
+
+    let mut builder = OCTOCRAB.gists().create().public(public);
+    if let Some(description) = description {
+        builder = builder.description(description);
+    }
+    for (name, content) in contents {
+        builder = builder.file(name, content);
+    }
+    let gist = builder.send().await?;
+    let html_url = gist.html_url.to_string();
+
+    // so the new gist shows up in the list straight away
+    let cached = ALFRED_WORKFLOW_CACHE.as_ref()?.join("gists.json");
+    if cached.exists() {
+        fs::remove_file(cached).await?;
+    }
+
+    let output: Output = AlfredWorkflow::builder()
+        .arg(&html_url)
+        .variables(json!({
+            "full_name": format!("gist {}", gist.id),
+            "html_url": html_url,
+        }))
+        .build();
+    let json = serde_json::to_string(&output)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+async fn clipboard() -> Result<String> {
+    let output = Command::new("pbpaste").output().await?;
+    if !output.status.success() {
+        return Err(eyre::eyre!("pbpaste failed with {}", output.status));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gist_matches() {
+        let files = ["deploy.sh".to_string(), "README.md".to_string()];
+        let description = Some("Staging deploy script");
+        assert!(gist_matches(description, files.iter(), ""));
+        assert!(gist_matches(description, files.iter(), "readme"));
+        assert!(gist_matches(description, files.iter(), "staging sh"));
+        assert!(!gist_matches(description, files.iter(), "staging py"));
+        assert!(gist_matches(None, files.iter(), "deploy"));
+    }
+}
@@ -0,0 +1,295 @@
+use clap::ValueEnum;
+use eyre::{ContextCompat, Result};
+use itertools::Itertools;
+use octocrab::params::{self, issues::Filter};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    alfred::{
+        models::{AlfredWorkflow, Item, Items, Output},
+        AuthorIcon,
+    },
+    args::{IssueFilter, IssueState},
+    github_util, FullName, OCTOCRAB,
+};
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct IssuesConfig {
+    /// where `issue create` puts issues when no owner/name is given
+    default_repo: Option<FullName>,
+}
+
+pub async fn run(repo: FullName, filter: IssueFilter) -> Result<()> {
+    let labels = filter.labels;
+    let issues = OCTOCRAB.issues(&repo.owner, &repo.name);
+    let mut list = issues
+        .list()
+        .state(match filter.state {
+            IssueState::Open => params::State::Open,
+            IssueState::Closed => params::State::Closed,
+            IssueState::All => params::State::All,
+        })
+        .page(filter.page)
+        .per_page(15u8);
+    if !labels.is_empty() {
+        list = list.labels(&labels);
+    }
+    if let Some(assignee) = &filter.assignee {
+        list = list.assignee(parse_filter(assignee, Ok)?);
+    }
+    if let Some(milestone) = &filter.milestone {
+        list = list.milestone(parse_filter(milestone, |number| Ok(number.parse()?))?);
+    }
+    let page = list.send().await?;
+    let has_next = page.next.is_some();
+
+    let mut items = page
+        .into_iter()
+        // the issues api also returns pull requests
+        .filter(|issue| issue.pull_request.is_none())
+        .map(|issue| {
+            let html_url = issue.html_url.to_string();
+            let mut subtitle = format!(
+                "#{number} opened by {login}",
+                number = issue.number,
+                login = issue.user.login
+            );
+            if !issue.labels.is_empty() {
+                let labels = issue.labels.iter().map(|l| &l.name).join(", ");
+                subtitle.push_str(&format!(" · {labels}"));
+            }
+            if issue.comments > 0 {
+                subtitle.push_str(&format!(" · {} comments", issue.comments));
+            }
+
+            Item::builder()
+                .title(issue.title.clone())
+                .subtitle(subtitle)
+                .arg(&html_url)
+                .icon(AuthorIcon::from(&issue.user))
+                .variables(json!({
+                    "created_at": issue.created_at,
+                    "updated_at": issue.updated_at,
+                    "full_name": format!("{repo}#{number}", number = issue.number),
+                    "html_url": html_url,
+                    "owner": issue.user.login,
+                }))
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    if has_next {
+        let page = filter.page + 1;
+        let mut rerun = json!({ "full_name": repo.to_string() });
+        rerun["action"] = json!("issues");
+        rerun["state"] = json!(filter
+            .state
+            .to_possible_value()
+            .map(|v| v.get_name().to_string()));
+        // the workflow splits these on commas to repeat --label
+        rerun["labels"] = json!(labels.join(","));
+        rerun["assignee"] = json!(filter.assignee);
+        rerun["milestone"] = json!(filter.milestone);
+        rerun["page"] = json!(page);
+        items.push(
+            Item::builder()
+                .title("More issues…")
+                // pull requests take up room in each page of the api, so pages can come up short
+                .subtitle(format!(
+                    "Show page {page} of {repo} issues (approximate, pull requests are left out)"
+                ))
+                .variables(rerun)
+                .build(),
+        );
+    }
+    let items = Items::from_iter(items);
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+/// `*` matches anything and `none` matches nothing, like the github api.
+fn parse_filter<'a, T>(
+    value: &'a str,
+    parse: impl FnOnce(&'a str) -> Result<T>,
+) -> Result<Filter<T>> {
+    match value {
+        "*" => Ok(Filter::Any),
+        "none" => Ok(Filter::None),
+        value => Ok(Filter::Matches(parse(value)?)),
+    }
+}
+
+/// Create an issue and output its url as the arg.
+pub async fn create(config: &IssuesConfig, args: Vec<String>) -> Result<()> {
+    let new_issue = NewIssue::parse(&args)?;
+    let repo = new_issue
+        .repo
+        .or_else(|| config.default_repo.clone())
+        .wrap_err("no owner/name given and no default_repo configured")?;
+    let issue = OCTOCRAB
+        .issues(&repo.owner, &repo.name)
+        .create(new_issue.title)
+        .body::<String>(new_issue.body)
+        .labels((!new_issue.labels.is_empty()).then_some(new_issue.labels))
+        .assignees((!new_issue.assignees.is_empty()).then_some(new_issue.assignees))
+        .send()
+        .await?;
+    let html_url = issue.html_url.to_string();
+
+    let output: Output = AlfredWorkflow::builder()
+        .arg(&html_url)
+        .variables(json!({
+            "full_name": format!("{repo}#{number}", number = issue.number),
+            "html_url": html_url,
+        }))
+        .build();
+    let json = serde_json::to_string(&output)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct NewIssue {
+    repo: Option<FullName>,
+    title: String,
+    body: Option<String>,
+    labels: Vec<String>,
+    assignees: Vec<String>,
+}
+
+impl NewIssue {
+    /// Parses `[owner/name] title [body]`, either as separate arguments
+    /// or as a single string the way alfred passes {query}, see [split_query].
+    ///
+    /// Words in the title starting with + are labels and @ are assignees.
+    fn parse(args: &[String]) -> Result<Self> {
+        let mut args = args.iter().map(|arg| arg.trim()).collect::<Vec<_>>();
+        if let [query] = args[..] {
+            args = split_query(query);
+        }
+        let repo = match args.first().map(|first| first.parse::<FullName>()) {
+            Some(Ok(repo)) if args[0].contains('/') => {
+                args.remove(0);
+                Some(repo)
+            }
+            _ => None,
+        };
+        let (title, body) = match args.split_first() {
+            Some((title, body)) => (title, body),
+            None => return Err(eyre::eyre!("missing issue title")),
+        };
+
+        let mut labels = vec![];
+        let mut assignees = vec![];
+        let mut words = vec![];
+        for word in title.split_whitespace() {
+            match (word.strip_prefix('+'), word.strip_prefix('@')) {
+                (Some(label), _) if !label.is_empty() => labels.push(label.to_string()),
+                (_, Some(login)) if !login.is_empty() => assignees.push(login.to_string()),
+                _ => words.push(word),
+            }
+        }
+        if words.is_empty() {
+            return Err(eyre::eyre!("missing issue title"));
+        }
+
+        Ok(Self {
+            repo,
+            title: words.join(" "),
+            body: (!body.is_empty()).then(|| body.join("\n\n")),
+            labels,
+            assignees,
+        })
+    }
+}
+
+/// In a single string the repo is only split off in front of a quoted title,
+/// `owner/name "title" body`, so that `fix/typo in readme` is all title.
+/// `"title" body` goes to the default repo.
+fn split_query(query: &str) -> Vec<&str> {
+    let (repo, rest) = match query.split_once(char::is_whitespace) {
+        Some((repo, rest)) if repo.contains('/') && rest.trim_start().starts_with('"') => {
+            (Some(repo), rest.trim_start())
+        }
+        _ => (None, query),
+    };
+    let Some((title, body)) = rest.strip_prefix('"').and_then(|rest| rest.split_once('"')) else {
+        return vec![query];
+    };
+    let body = Some(body.trim()).filter(|body| !body.is_empty());
+
+    repo.into_iter().chain([title.trim()]).chain(body).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_new_issue() {
+        assert_eq!(
+            NewIssue::parse(&args(&["a/b", "it broke +bug @alice", "details"])).unwrap(),
+            NewIssue {
+                repo: Some("a/b".parse().unwrap()),
+                title: "it broke".to_string(),
+                body: Some("details".to_string()),
+                labels: vec!["bug".to_string()],
+                assignees: vec!["alice".to_string()],
+            }
+        );
+        assert_eq!(
+            NewIssue::parse(&args(&[r#"a/b "it broke +bug" details"#])).unwrap(),
+            NewIssue {
+                repo: Some("a/b".parse().unwrap()),
+                title: "it broke".to_string(),
+                body: Some("details".to_string()),
+                labels: vec!["bug".to_string()],
+                assignees: vec![],
+            }
+        );
+        // unquoted, the first word is part of the title even if it looks like a repo
+        assert_eq!(
+            NewIssue::parse(&args(&["fix/typo in readme +docs"])).unwrap(),
+            NewIssue {
+                repo: None,
+                title: "fix/typo in readme".to_string(),
+                body: None,
+                labels: vec!["docs".to_string()],
+                assignees: vec![],
+            }
+        );
+        assert_eq!(
+            NewIssue::parse(&args(&[r#""fix/typo in readme" it says teh"#])).unwrap(),
+            NewIssue {
+                repo: None,
+                title: "fix/typo in readme".to_string(),
+                body: Some("it says teh".to_string()),
+                labels: vec![],
+                assignees: vec![],
+            }
+        );
+        assert_eq!(
+            NewIssue::parse(&args(&["C++ is hard"])).unwrap(),
+            NewIssue {
+                repo: None,
+                title: "C++ is hard".to_string(),
+                body: None,
+                labels: vec![],
+                assignees: vec![],
+            }
+        );
+        assert!(NewIssue::parse(&args(&["a/b"])).is_err());
+        assert!(NewIssue::parse(&args(&["+bug @alice"])).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use eyre::Result;
+use futures::future::join_all;
+use itertools::Itertools;
+use octocrab::models::{activity::Notification, NotificationId, ThreadId};
+use serde_json::{json, Value};
+
+use crate::{
+    alfred::{AuthorIcon, Icon, Item, Items, Modifier, Modifiers},
+    args::NotificationMethod,
+    github_util, OCTOCRAB,
+};
+
+/// Unread notifications, with a header item for each repo.
+pub async fn run() -> Result<()> {
+    let octocrab = OCTOCRAB.clone();
+    let page = octocrab
+        .activity()
+        .notifications()
+        .list()
+        .per_page(50u8)
+        .send()
+        .await?;
+    let notifications = octocrab
+        .all_pages(page)
+        .await?
+        .into_iter()
+        .sorted_by(|a, b| a.repository.full_name.cmp(&b.repository.full_name))
+        .collect::<Vec<_>>();
+    // some subjects take a request to find their page, so resolve them all at once
+    let html_urls = join_all(notifications.iter().map(html_url)).await;
+
+    let mut items = vec![];
+    let by_repo = notifications
+        .into_iter()
+        .zip(html_urls)
+        .group_by(|(n, _)| n.repository.full_name.clone().unwrap_or_default());
+    for (full_name, notifications) in &by_repo {
+        let notifications = notifications.collect::<Vec<_>>();
+        let owner = full_name
+            .split_once('/')
+            .map(|(owner, _)| owner.to_string());
+        items.push(
+            Item::builder()
+                .title(&full_name)
+                .subtitle(format!("{} unread", notifications.len()))
+                .valid(false)
+                .icon(AuthorIcon::from(
+                    notifications[0].0.repository.owner.clone(),
+                ))
+                .variables(json!({ "owner": owner }))
+                .build(),
+        );
+        for (notification, html_url) in notifications {
+            items.push(notification_to_item(notification, html_url));
+        }
+    }
+    let items = Items::from_iter(items);
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+pub async fn update(method: NotificationMethod) -> Result<()> {
+    let octocrab = OCTOCRAB.clone();
+    match method {
+        NotificationMethod::Read { thread } => {
+            octocrab
+                .activity()
+                .notifications()
+                .mark_as_read(NotificationId(thread))
+                .await?;
+            println!("Marked as read");
+        }
+        NotificationMethod::Done { thread } => {
+            let route = format!("/notifications/threads/{thread}");
+            octocrab::map_github_error(octocrab._delete(route, None::<&()>).await?).await?;
+            println!("Marked as done");
+        }
+        NotificationMethod::Unsubscribe { thread } => {
+            octocrab
+                .activity()
+                .notifications()
+                .delete_thread_subscription(ThreadId(thread))
+                .await?;
+            println!("Unsubscribed");
+        }
+    }
+
+    Ok(())
+}
+
+fn notification_to_item(notification: Notification, html_url: String) -> Item {
+    let thread = notification.id.0;
+    let subject = &notification.subject;
+    let icon = match subject.r#type.as_str() {
+        "Issue" => "issue",
+        "PullRequest" => "pull-request",
+        "Release" => "release",
+        "Discussion" => "discussion",
+        "Commit" => "commit",
+        "CheckSuite" | "WorkflowRun" => "workflow",
+        "RepositoryVulnerabilityAlert"
+        | "RepositoryDependabotAlertsThread"
+        | "SecurityAdvisory" => "alert",
+        _ => "notification",
+    };
+    let modifier = |subtitle: &str, method: &str| {
+        Modifier::builder()
+            .subtitle(subtitle)
+            .variables(json!({
+                "action": "notification",
+                "method": method,
+                "thread": thread,
+            }))
+            .build()
+    };
+
+    Item::builder()
+        .title(&subject.title)
+        .subtitle(format!(
+            "{reason} · {type}",
+            reason = notification.reason.replace('_', " "),
+            r#type = subject.r#type,
+        ))
+        .uid(thread.to_string())
+        .arg(&html_url)
+        .icon(Icon::bundled(icon))
+        .variables(json!({
+            "updated_at": notification.updated_at,
+            "full_name": notification.repository.full_name,
+            "html_url": html_url,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(modifier("Mark as read", "read"))
+                .alt(modifier("Mark as done", "done"))
+                .ctrl(modifier("Unsubscribe", "unsubscribe"))
+                .build(),
+        )
+        .build()
+}
+
+/// The subject url points at the api, this finds the page on github for it.
+///
+/// Issues, pulls and commits map directly, anything else is fetched
+/// and falls back to a page on the repo when that fails.
+async fn html_url(notification: &Notification) -> String {
+    let repo_url = match &notification.repository.html_url {
+        Some(url) => url.to_string(),
+        None => format!(
+            "https://github.com/{}",
+            notification
+                .repository
+                .full_name
+                .as_deref()
+                .unwrap_or_default()
+        ),
+    };
+    let fallback = match notification.subject.r#type.as_str() {
+        "CheckSuite" | "WorkflowRun" => format!("{repo_url}/actions"),
+        "Discussion" => format!("{repo_url}/discussions"),
+        "RepositoryVulnerabilityAlert" | "RepositoryDependabotAlertsThread" => {
+            format!("{repo_url}/security/dependabot")
+        }
+        _ => repo_url.clone(),
+    };
+    let Some(api_url) = &notification.subject.url else {
+        return fallback;
+    };
+    // e.g. https://api.github.com/repos/owner/name/pulls/12
+    let path = api_url
+        .path()
+        .split('/')
+        .skip_while(|s| *s != "repos")
+        .skip(3)
+        .collect::<Vec<_>>();
+    match path.as_slice() {
+        ["pulls", number] => format!("{repo_url}/pull/{number}"),
+        ["issues", number] => format!("{repo_url}/issues/{number}"),
+        ["commits", sha] => format!("{repo_url}/commit/{sha}"),
+        _ => {
+            let subject: Result<Value, _> = OCTOCRAB.get(api_url.as_str(), None::<&()>).await;
+            let html_url = subject
+                .ok()
+                .and_then(|s| Some(s.get("html_url")?.as_str()?.to_string()));
+            html_url.unwrap_or(fallback)
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::env;
+
+use eyre::{ContextCompat, Result};
+use octocrab::models::{
+    issues::{Issue, IssueStateReason},
+    IssueState,
+};
+use serde_json::json;
+use tokio::process::Command;
+
+use crate::{
+    actions::{
+        commits::commit_to_item,
+        pulls::pull_to_item,
+        repos::{repository_to_item, ReposConfig},
+    },
+    alfred::{AuthorIcon, Item, Items},
+    github_util,
+    reference::{Reference, Target},
+    FullName, OCTOCRAB,
+};
+
+/// Turn pasted text into the github object it refers to.
+///
+/// Bare `#123` and commit shas are resolved against `repo`,
+/// or the repo open in the frontmost browser tab.
+pub async fn run(config: &ReposConfig, input: String, repo: Option<FullName>) -> Result<()> {
+    let item = match resolve(&input, repo).await {
+        Ok(reference) => reference_to_item(config, reference).await,
+        Err(err) => Err(err),
+    };
+    let item = item.unwrap_or_else(|err| {
+        Item::builder()
+            .title(format!("Can't open {input}"))
+            .subtitle(err.to_string())
+            .valid(false)
+            .build()
+    });
+    let items = Items::from_iter([item]);
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+async fn resolve(input: &str, repo: Option<FullName>) -> Result<Reference> {
+    if let Ok(reference) = input.parse::<Reference>() {
+        return Ok(reference);
+    }
+    let target = input.parse::<Target>().map_err(|err| eyre::eyre!(err))?;
+    let (host, full_name) = match repo {
+        Some(repo) => ("github.com".to_string(), repo),
+        None => frontmost_repo()
+            .await
+            .map(|reference| (reference.host, reference.full_name))
+            .wrap_err(format!("no repository to resolve {input} against"))?,
+    };
+
+    Ok(Reference {
+        host,
+        full_name,
+        target: Some(target),
+    })
+}
+
+/// The url in the focused browser, if it is a repository on any host.
+async fn frontmost_repo() -> Option<Reference> {
+    let focusedapp = env::var("focusedapp").ok()?;
+    let script = match focusedapp.as_str() {
+        "com.apple.Safari" => {
+            r#"tell application id "com.apple.Safari" to return URL of front document"#.to_string()
+        }
+        "com.google.Chrome" | "com.microsoft.edgemac" | "com.brave.Browser" => {
+            format!(
+                r#"tell application id "{focusedapp}" to return URL of active tab of front window"#
+            )
+        }
+        _ => return None,
+    };
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .await
+        .ok()?;
+
+    String::from_utf8(output.stdout).ok()?.parse().ok()
+}
+
+async fn reference_to_item(config: &ReposConfig, reference: Reference) -> Result<Item> {
+    if !reference.is_github() {
+        return Ok(url_to_item(&reference));
+    }
+    let FullName { owner, name } = &reference.full_name;
+    let octocrab = OCTOCRAB.clone();
+    match reference.target {
+        None => repository_to_item(config, octocrab.repos(owner, name).get().await?),
+        Some(Target::Pull(number)) => {
+            let pull = octocrab.pulls(owner, name).get(number).await?;
+            pull_to_item(&reference.full_name, pull)
+        }
+        Some(Target::Issue(number) | Target::Number(number)) => {
+            let issue = octocrab.issues(owner, name).get(number).await?;
+            if issue.pull_request.is_some() {
+                let pull = octocrab.pulls(owner, name).get(number).await?;
+                pull_to_item(&reference.full_name, pull)
+            } else {
+                Ok(issue_to_item(&reference.full_name, issue))
+            }
+        }
+        Some(Target::Commit(sha)) => {
+            let commit = octocrab.commits(owner, name).get(sha).await?;
+            Ok(commit_to_item(&reference.full_name, commit))
+        }
+    }
+}
+
+/// References on other hosts, e.g. github enterprise, can't be looked up with
+/// the github.com token, so they just open in the browser.
+fn url_to_item(reference: &Reference) -> Item {
+    let html_url = reference.html_url();
+
+    Item::builder()
+        .title(&html_url)
+        .subtitle(format!(
+            "Open {} on {}",
+            reference.full_name, reference.host
+        ))
+        .arg(&html_url)
+        .variables(json!({
+            "full_name": reference.full_name.to_string(),
+            "html_url": html_url,
+        }))
+        .build()
+}
+
+fn issue_to_item(repo: &FullName, issue: Issue) -> Item {
+    let state = match (&issue.state, &issue.state_reason) {
+        (IssueState::Closed, Some(IssueStateReason::NotPlanned)) => "closed as not planned",
+        (IssueState::Closed, _) => "closed",
+        _ => "open",
+    };
+    let full_name = format!("{repo}#{number}", number = issue.number);
+    let html_url = issue.html_url.to_string();
+
+    Item::builder()
+        .title(issue.title)
+        .subtitle(format!(
+            "{full_name} {state} issue opened by {login}",
+            login = issue.user.login
+        ))
+        .arg(&html_url)
+        .icon(AuthorIcon::from(&issue.user))
+        .variables(json!({
+            "created_at": issue.created_at,
+            "updated_at": issue.updated_at,
+            "full_name": full_name,
+            "html_url": html_url,
+            "owner": issue.user.login,
+        }))
+        .build()
+}
@@ -0,0 +1,269 @@
+use eyre::{ContextCompat, Result};
+use itertools::Itertools;
+use serde_json::json;
+
+use crate::{
+    alfred::{Icon, Item, Items, Modifier, Modifiers},
+    args::ProjectMethod,
+    graphql::{
+        self,
+        move_project_item::{self, MoveProjectItem},
+        project_items::{self, ProjectItem, ProjectItems, StatusOption},
+        viewer_projects::{Project, ViewerProjects},
+    },
+};
+
+/// Open projects of the user and their orgs, most recently updated first.
+pub async fn run() -> Result<()> {
+    let viewer = graphql::query::<ViewerProjects>(&()).await?.viewer;
+    let login = viewer.login;
+    let mut projects = viewer
+        .projects_v2
+        .nodes
+        .into_iter()
+        .map(|project| (login.clone(), project))
+        .collect::<Vec<_>>();
+    for org in viewer.organizations.nodes {
+        let Some(connection) = org.projects_v2 else {
+            continue;
+        };
+        projects.extend(
+            connection
+                .nodes
+                .into_iter()
+                .map(|project| (org.login.clone(), project)),
+        );
+    }
+    let items: Items = projects
+        .into_iter()
+        .filter(|(_, project)| !project.closed)
+        .sorted_by(|(_, a), (_, b)| b.updated_at.cmp(&a.updated_at))
+        .map(|(owner, project)| project_to_item(&owner, project))
+        .collect();
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+fn project_to_item(owner: &str, project: Project) -> Item {
+    let mut subtitle = format!("{owner} #{number}", number = project.number);
+    if let Some(description) = project.short_description.filter(|d| !d.is_empty()) {
+        subtitle.push_str(&format!(" · {description}"));
+    }
+
+    Item::builder()
+        .title(project.title)
+        .subtitle(subtitle)
+        .uid(&project.id)
+        .arg(&project.url)
+        .icon(Icon::bundled("project"))
+        .variables(json!({
+            "updated_at": project.updated_at,
+            "full_name": format!("{owner}/projects/{number}", number = project.number),
+            "html_url": &project.url,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(
+                    Modifier::builder()
+                        .subtitle("List items by status")
+                        .variables(json!({
+                            "action": "project-items",
+                            "project": &project.id,
+                        }))
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+/// The items of a project, with a header item for each Status column.
+pub async fn items(project: String) -> Result<()> {
+    let mut variables = project_items::Variables {
+        project,
+        after: None,
+    };
+    let mut items = vec![];
+    let (title, url, field) = loop {
+        let node = graphql::query::<ProjectItems>(&variables)
+            .await?
+            .node
+            .wrap_err("no such project")?;
+        variables.after = node.items.next_cursor();
+        items.extend(node.items.nodes);
+        if variables.after.is_none() {
+            break (node.title, node.url, node.field);
+        }
+    };
+    let (field, options) = match field {
+        Some(project_items::StatusField {
+            id: Some(id),
+            options,
+        }) => (Some(id), options),
+        _ => (None, vec![]),
+    };
+    let project = &variables.project;
+
+    let mut output = vec![];
+    let by_status = items.into_iter().into_group_map_by(|item| {
+        item.status
+            .as_ref()
+            .and_then(|status| status.option_id.clone())
+    });
+    let columns = options
+        .iter()
+        .map(|option| (Some(option.id.clone()), option.name.as_str()))
+        .chain([(None, "No Status")]);
+    for (option_id, name) in columns {
+        let Some(column) = by_status.get(&option_id) else {
+            continue;
+        };
+        output.push(
+            Item::builder()
+                .title(name)
+                .subtitle(format!("{} items in {title}", column.len()))
+                .valid(false)
+                .icon(Icon::bundled("project"))
+                .build(),
+        );
+        for item in column {
+            let position = options
+                .iter()
+                .position(|option| Some(&option.id) == option_id.as_ref());
+            let (previous, next) = match position {
+                Some(position) => (
+                    position.checked_sub(1).and_then(|i| options.get(i)),
+                    options.get(position + 1),
+                ),
+                None => (None, options.first()),
+            };
+            let moves = Moves {
+                project,
+                field: field.as_deref(),
+                previous,
+                next,
+            };
+            output.push(project_item_to_item(&url, item, moves));
+        }
+    }
+    let output = Items::from_iter(output);
+
+    let json = serde_json::to_string(&output)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+/// Where the modifiers of a project item move it to.
+struct Moves<'a> {
+    project: &'a str,
+    field: Option<&'a str>,
+    previous: Option<&'a StatusOption>,
+    next: Option<&'a StatusOption>,
+}
+
+impl Moves<'_> {
+    fn modifier(&self, item: &str, to: Option<&StatusOption>) -> Modifier {
+        match (self.field, to) {
+            (Some(field), Some(to)) => Modifier::builder()
+                .subtitle(format!("Move to {}", to.name))
+                .variables(json!({
+                    "action": "project-move",
+                    "project": self.project,
+                    "item": item,
+                    "field": field,
+                    "option": &to.id,
+                    "status": &to.name,
+                }))
+                .build(),
+            _ => Modifier::builder()
+                .subtitle("Can't move any further")
+                .valid(false)
+                .build(),
+        }
+    }
+}
+
+fn project_item_to_item(project_url: &str, item: &ProjectItem, moves: Moves) -> Item {
+    let (kind, icon) = match item.r#type.as_str() {
+        "ISSUE" => ("issue", "issue"),
+        "PULL_REQUEST" => ("pull request", "pull-request"),
+        "DRAFT_ISSUE" => ("draft", "draft"),
+        _ => ("redacted", "draft"),
+    };
+    let mut subtitle = kind.to_string();
+    let mut html_url = project_url.to_string();
+    let mut title = "Redacted item".to_string();
+    let mut full_name = None;
+    if let Some(content) = &item.content {
+        title = content.title.clone();
+        if let (Some(repo), Some(number)) = (&content.repository, content.number) {
+            let reference = format!("{}#{number}", repo.name_with_owner);
+            subtitle.push_str(&format!(" · {reference}"));
+            full_name = Some(reference);
+        }
+        if !content.assignees.nodes.is_empty() {
+            let assignees = content
+                .assignees
+                .nodes
+                .iter()
+                .map(|a| format!("@{}", a.login))
+                .join(", ");
+            subtitle.push_str(&format!(" · {assignees}"));
+        }
+        if let Some(url) = &content.url {
+            html_url = url.clone();
+        }
+    }
+    let iteration = item
+        .field_values
+        .nodes
+        .iter()
+        .find_map(|value| value.title.as_ref());
+    if let Some(iteration) = iteration {
+        subtitle.push_str(&format!(" · {iteration}"));
+    }
+
+    Item::builder()
+        .title(&title)
+        .subtitle(subtitle)
+        .uid(&item.id)
+        .arg(&html_url)
+        .icon(Icon::bundled(icon))
+        .variables(json!({
+            "full_name": full_name.unwrap_or_else(|| title.clone()),
+            "html_url": html_url,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(moves.modifier(&item.id, moves.next))
+                .alt(moves.modifier(&item.id, moves.previous))
+                .build(),
+        )
+        .build()
+}
+
+pub async fn update(method: ProjectMethod) -> Result<()> {
+    match method {
+        ProjectMethod::Move {
+            project,
+            item,
+            field,
+            option,
+        } => {
+            let variables = move_project_item::Variables {
+                project,
+                item,
+                field,
+                option,
+            };
+            graphql::query::<MoveProjectItem>(&variables).await?;
+            println!("Moved");
+        }
+    }
+
+    Ok(())
+}
@@ -1,19 +1,65 @@
-use eyre::Result;
+use eyre::{ContextCompat, Result};
 
+use octocrab::{
+    models::{pulls::PullRequest, IssueState},
+    params,
+};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::{
     alfred::{
-        models::{Item, Items},
-        AuthorIcon,
+        models::{AlfredWorkflow, Item, Items, Modifier, Modifiers, Output},
+        AuthorIcon, Icon,
+    },
+    args::{PullArgs, PullMethod},
+    graphql::{
+        self,
+        enable_auto_merge::{self, EnableAutoMerge},
     },
     FullName, OCTOCRAB,
 };
 
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PullsConfig {
+    /// how `pull merge` and `pull auto-merge` merge
+    merge_method: MergeMethod,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    #[default]
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Squash => "squash",
+            Self::Rebase => "rebase",
+        }
+    }
+}
+
+impl From<MergeMethod> for params::pulls::MergeMethod {
+    fn from(method: MergeMethod) -> Self {
+        match method {
+            MergeMethod::Merge => Self::Merge,
+            MergeMethod::Squash => Self::Squash,
+            MergeMethod::Rebase => Self::Rebase,
+        }
+    }
+}
+
 pub async fn run(repo: FullName) -> Result<()> {
     let pulls = OCTOCRAB
         .clone()
-        .pulls(repo.owner, repo.name)
+        .pulls(&repo.owner, &repo.name)
         .list()
         .page(1u8)
         .per_page(15u8)
@@ -21,38 +67,275 @@ pub async fn run(repo: FullName) -> Result<()> {
         .await?;
     let items = pulls
         .into_iter()
-        .flat_map(|pull| {
-            let html_url = pull.html_url.clone()?.to_string();
-            Some(
-                Item::builder()
-                    .title(pull.title.clone()?)
-                    .subtitle(format!(
-                        "#{number} opened by {login}",
-                        number = pull.number,
-                        login = pull.user.as_ref().map(|u| u.login.as_str()).unwrap_or("")
-                    ))
-                    .arg(html_url)
-                    .icon(AuthorIcon::from(&pull.user))
-                    .variables({
-                        let pull = &pull;
-                        let full_name = pull.base.clone().repo?.full_name?;
-                        let number = pull.number;
-
-                        Some(json! {
-                            {
-                                "created_at": pull.created_at,
-                                "updated_at": pull.updated_at,
-                                "full_name": format!("{full_name}#{number}")
-                            }
-                        })
-                    })
-                    .build(),
-            )
-        })
-        .collect::<Items>();
+        .map(|pull| pull_to_item(&repo, pull))
+        .collect::<Result<Items>>()?;
 
     let json = serde_json::to_string(&items)?;
     println!("{}", json);
 
     Ok(())
 }
+
+/// Review and merge modifiers for a pull request item, each leads to a confirmation.
+pub fn pull_modifiers(repo: &FullName, number: u64) -> Modifiers {
+    let modifier = |subtitle: &str, method: &str| {
+        Modifier::builder()
+            .subtitle(subtitle)
+            .variables(json!({
+                "action": "pull",
+                "method": method,
+                "repo": repo.to_string(),
+                "number": number,
+            }))
+            .build()
+    };
+
+    Modifiers::builder()
+        .cmd(modifier("Approve…", "approve"))
+        .alt(modifier("Request changes…", "request-changes"))
+        .ctrl(modifier("Merge…", "merge"))
+        .shift(modifier("Enable auto-merge…", "auto-merge"))
+        .build()
+}
+
+/// An item for a pull request in any state, with the review and merge modifiers.
+pub fn pull_to_item(repo: &FullName, pull: PullRequest) -> Result<Item> {
+    let state = match (&pull.state, pull.merged_at, pull.draft) {
+        (_, Some(_), _) => "merged",
+        (Some(IssueState::Closed), _, _) => "closed",
+        (_, _, Some(true)) => "draft",
+        _ => "open",
+    };
+    let full_name = format!("{repo}#{number}", number = pull.number);
+    let html_url = pull
+        .html_url
+        .clone()
+        .wrap_err("html_url is None")?
+        .to_string();
+    let login = pull.user.as_ref().map(|u| u.login.clone());
+
+    let item = Item::builder()
+        .title(pull.title.clone().unwrap_or_default())
+        .subtitle(format!(
+            "{full_name} {state} pull request opened by {login}",
+            login = login.as_deref().unwrap_or_default()
+        ))
+        .arg(&html_url)
+        .icon(AuthorIcon::from(&pull.user))
+        .variables(json!({
+            "created_at": pull.created_at,
+            "updated_at": pull.updated_at,
+            "full_name": full_name,
+            "html_url": html_url,
+            "owner": login,
+        }))
+        .mods(pull_modifiers(repo, pull.number))
+        .build();
+
+    Ok(item)
+}
+
+/// Without --confirm print the script filter item that asks for confirmation,
+/// with it do the thing and report how it went in the `result` variable.
+pub async fn update(config: &PullsConfig, method: PullMethod) -> Result<()> {
+    let (args, verb, body) = match &method {
+        PullMethod::Approve { pull, body } => (pull, "approve", body.as_deref()),
+        PullMethod::RequestChanges { pull, body } => (pull, "request-changes", body.as_deref()),
+        PullMethod::Merge { pull } => (pull, "merge", None),
+        PullMethod::AutoMerge { pull } => (pull, "auto-merge", None),
+    };
+    let pull = OCTOCRAB
+        .pulls(&args.repo.owner, &args.repo.name)
+        .get(args.number)
+        .await?;
+    if !args.confirm {
+        let item = confirmation_item(config, args, verb, body, &pull);
+        let json = serde_json::to_string(&Items::from_iter([item]))?;
+        println!("{}", json);
+
+        return Ok(());
+    }
+
+    let full_name = format!("{}#{}", args.repo, args.number);
+    let (result, message) = match perform(config, args, &method, &pull).await {
+        Ok(message) => ("success", format!("{full_name} {message}")),
+        Err(err) => ("failure", format!("{full_name}: {err}")),
+    };
+    let output: Output = AlfredWorkflow::builder()
+        .arg(&message)
+        .variables(json!({
+            "result": result,
+            "message": message,
+            "full_name": full_name,
+            "html_url": pull.html_url.as_ref().map(|url| url.to_string()),
+        }))
+        .build();
+    let json = serde_json::to_string(&output)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+fn confirmation_item(
+    config: &PullsConfig,
+    args: &PullArgs,
+    verb: &str,
+    body: Option<&str>,
+    pull: &PullRequest,
+) -> Item {
+    let title = pull.title.clone().unwrap_or_default();
+    let body = body.unwrap_or_default().trim();
+    let method = config.merge_method.as_str();
+    let label = match verb {
+        "approve" => "Approve",
+        "request-changes" => "Request changes on",
+        "merge" => "Merge",
+        _ => "Auto-merge",
+    };
+    let (subtitle, valid) = match verb {
+        "approve" if body.is_empty() => ("↩ to approve, or type a comment first".to_string(), true),
+        "approve" => (format!("↩ to approve with “{body}”"), true),
+        "request-changes" if body.is_empty() => ("Type what needs to change".to_string(), false),
+        "request-changes" => (format!("↩ to request changes with “{body}”"), true),
+        _ if pull.merged_at.is_some() => ("Already merged".to_string(), false),
+        _ if pull.mergeable == Some(false) => {
+            ("Not mergeable, check for conflicts".to_string(), false)
+        }
+        "merge" => (format!("↩ to {method} merge"), true),
+        _ => (format!("↩ to {method} merge once checks pass"), true),
+    };
+
+    Item::builder()
+        .title(format!(
+            "{label} {repo}#{number}: {title}",
+            repo = args.repo,
+            number = args.number
+        ))
+        .subtitle(subtitle)
+        .arg(body)
+        .valid(valid)
+        .icon(Icon::bundled("pull-request"))
+        .variables(json!({
+            "action": "pull",
+            "method": verb,
+            "repo": args.repo.to_string(),
+            "number": args.number,
+            "confirm": true,
+        }))
+        .build()
+}
+
+/// Returns what happened, e.g. "approved".
+async fn perform(
+    config: &PullsConfig,
+    args: &PullArgs,
+    method: &PullMethod,
+    pull: &PullRequest,
+) -> Result<&'static str> {
+    let route = format!("/repos/{}/pulls/{}/reviews", args.repo, args.number);
+    match method {
+        PullMethod::Approve { body, .. } => {
+            let _: serde_json::Value = OCTOCRAB
+                .post(
+                    route,
+                    Some(&json!({
+                        "event": "APPROVE",
+                        "body": body.as_deref().unwrap_or_default(),
+                    })),
+                )
+                .await?;
+            Ok("approved")
+        }
+        PullMethod::RequestChanges { body, .. } => {
+            let body = body.as_deref().filter(|body| !body.trim().is_empty());
+            let body = body.wrap_err("requesting changes needs a comment")?;
+            let _: serde_json::Value = OCTOCRAB
+                .post(
+                    route,
+                    Some(&json!({ "event": "REQUEST_CHANGES", "body": body })),
+                )
+                .await?;
+            Ok("changes requested")
+        }
+        PullMethod::Merge { .. } => {
+            let merge = OCTOCRAB
+                .pulls(&args.repo.owner, &args.repo.name)
+                .merge(args.number)
+                .method(config.merge_method)
+                .send()
+                .await?;
+            match merge.merged {
+                true => Ok("merged"),
+                false => Err(eyre::eyre!(merge.message.unwrap_or_default())),
+            }
+        }
+        PullMethod::AutoMerge { .. } => {
+            let variables = enable_auto_merge::Variables {
+                pull: pull.node_id.clone().wrap_err("node_id is None")?,
+                method: config.merge_method.as_str().to_uppercase(),
+            };
+            graphql::query::<EnableAutoMerge>(&variables).await?;
+            Ok("will be merged when ready")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    fn confirmation(verb: &str, body: Option<&str>, pull: &PullRequest) -> (String, bool) {
+        let config = PullsConfig {
+            merge_method: MergeMethod::Squash,
+        };
+        let args = PullArgs {
+            repo: "wayofthepie/test-events".parse().unwrap(),
+            number: 8,
+            confirm: false,
+        };
+        let item =
+            serde_json::to_value(confirmation_item(&config, &args, verb, body, pull)).unwrap();
+
+        (
+            item["subtitle"].as_str().unwrap().to_string(),
+            item["valid"] == Value::Bool(true),
+        )
+    }
+
+    #[test]
+    fn test_confirmation_item() {
+        let mut pull: PullRequest =
+            serde_json::from_str(include_str!("../../tests/fixtures/pull_request.json")).unwrap();
+        pull.mergeable = Some(true);
+
+        assert!(confirmation("approve", None, &pull).1);
+        assert!(confirmation("approve", Some("lgtm"), &pull).1);
+        assert!(!confirmation("request-changes", None, &pull).1);
+        assert!(!confirmation("request-changes", Some("  "), &pull).1);
+        assert!(confirmation("request-changes", Some("needs a test"), &pull).1);
+        assert_eq!(
+            confirmation("merge", None, &pull),
+            ("↩ to squash merge".to_string(), true)
+        );
+        assert_eq!(
+            confirmation("auto-merge", None, &pull),
+            ("↩ to squash merge once checks pass".to_string(), true)
+        );
+
+        pull.mergeable = Some(false);
+        assert_eq!(
+            confirmation("merge", None, &pull),
+            ("Not mergeable, check for conflicts".to_string(), false)
+        );
+
+        pull.merged_at = Some(chrono::Utc::now());
+        for verb in ["merge", "auto-merge"] {
+            assert_eq!(
+                confirmation(verb, None, &pull),
+                ("Already merged".to_string(), false)
+            );
+        }
+    }
+}
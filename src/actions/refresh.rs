@@ -1,12 +1,22 @@
 use eyre::Result;
 
-use crate::alfred::ALFRED_WORKFLOW_DATA;
+use crate::alfred::{ALFRED_WORKFLOW_CACHE, ALFRED_WORKFLOW_DATA};
 
+/// Forget the cached repos and stars, and the items made from them, which also picks
+/// up changes to the modifiers in the config, as well as the cached feed and gists.
 pub async fn run() -> Result<()> {
-    let repos_json = ALFRED_WORKFLOW_DATA.as_ref()?.join("repos.json");
-    if repos_json.exists() {
-        println!("removed {}", repos_json.to_string_lossy());
-        tokio::fs::remove_file(repos_json).await?;
+    let paths = [
+        ALFRED_WORKFLOW_DATA.as_ref()?.join("repos.json"),
+        ALFRED_WORKFLOW_DATA.as_ref()?.join("stars.json"),
+        ALFRED_WORKFLOW_DATA.as_ref()?.join("both.json"),
+        ALFRED_WORKFLOW_CACHE.as_ref()?.join("feed.json"),
+        ALFRED_WORKFLOW_CACHE.as_ref()?.join("gists.json"),
+    ];
+    for path in paths {
+        if path.exists() {
+            println!("removed {}", path.to_string_lossy());
+            tokio::fs::remove_file(path).await?;
+        }
     }
 
     Ok(())
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+
+use eyre::{ContextCompat, Result};
+use octocrab::models::{
+    repos::{Asset, Release},
+    AssetId,
+};
+use reqwest::header::ACCEPT;
+use serde_json::json;
+
+use crate::{
+    alfred::{Icon, Item, Items, Modifier, Modifiers},
+    github_util::{self, HTTP_CLIENT},
+    hub_compat::HubConfig,
+    FullName, OCTOCRAB,
+};
+
+/// Releases of a repo, followed by the tags that have no release.
+pub async fn run(repo: FullName) -> Result<()> {
+    let octocrab = OCTOCRAB.clone();
+    let handler = octocrab.repos(&repo.owner, &repo.name);
+    let releases = handler.releases().list().per_page(15u8).send().await?.items;
+    let tags = handler.list_tags().per_page(15u8).send().await?.items;
+
+    let released = releases
+        .iter()
+        .map(|release| release.tag_name.as_str())
+        .collect::<HashSet<_>>();
+    let tags = tags
+        .iter()
+        .filter(|tag| !released.contains(tag.name.as_str()))
+        .map(|tag| {
+            let html_url = format!("https://github.com/{repo}/releases/tag/{}", tag.name);
+            Item::builder()
+                .title(&tag.name)
+                .subtitle(format!("tag at {}", &tag.commit.sha[..7]))
+                .arg(&html_url)
+                .icon(Icon::bundled("tag"))
+                .variables(json!({
+                    "full_name": format!("{repo}@{}", tag.name),
+                    "html_url": html_url,
+                }))
+                .build()
+        })
+        .collect::<Vec<_>>();
+    let items: Items = releases
+        .iter()
+        .map(|release| release_to_item(&repo, release))
+        .chain(tags)
+        .collect();
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+fn release_to_item(repo: &FullName, release: &Release) -> Item {
+    let mut subtitle = release.tag_name.clone();
+    match release.published_at {
+        Some(published_at) => {
+            subtitle.push_str(&format!(" · published {}", published_at.format("%Y-%m-%d")))
+        }
+        None => subtitle.push_str(" · draft"),
+    }
+    if release.prerelease {
+        subtitle.push_str(" · pre-release");
+    }
+    if !release.assets.is_empty() {
+        subtitle.push_str(&format!(" · {} assets", release.assets.len()));
+    }
+    let html_url = release.html_url.to_string();
+
+    Item::builder()
+        .title(release.name.as_deref().unwrap_or(&release.tag_name))
+        .subtitle(subtitle)
+        .uid(release.id.0.to_string())
+        .arg(&html_url)
+        .icon(Icon::bundled("release"))
+        .variables(json!({
+            "created_at": release.created_at,
+            "updated_at": release.published_at,
+            "full_name": format!("{repo}@{}", release.tag_name),
+            "html_url": html_url,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(
+                    Modifier::builder()
+                        .subtitle("List assets")
+                        .valid(!release.assets.is_empty())
+                        .variables(json!({
+                            "action": "assets",
+                            "full_name": repo.to_string(),
+                            "release": release.id.0,
+                        }))
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+/// The downloadable assets of a release.
+pub async fn assets(repo: FullName, release: u64) -> Result<()> {
+    let release: Release = OCTOCRAB
+        .get(format!("/repos/{repo}/releases/{release}"), None::<&()>)
+        .await?;
+    let items: Items = release
+        .assets
+        .iter()
+        .map(|asset| asset_to_item(&repo, asset))
+        .collect();
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+fn asset_to_item(repo: &FullName, asset: &Asset) -> Item {
+    let html_url = asset.browser_download_url.to_string();
+
+    Item::builder()
+        .title(&asset.name)
+        .subtitle(format!(
+            "{size} · {count} downloads · download to ~/Downloads",
+            size = human_size(asset.size),
+            count = asset.download_count,
+        ))
+        .uid(asset.id.0.to_string())
+        .arg(&html_url)
+        .icon(Icon::bundled("download"))
+        .variables(json!({
+            "action": "download",
+            "full_name": repo.to_string(),
+            "asset": asset.id.0,
+            "html_url": html_url,
+        }))
+        .build()
+}
+
+/// Download a release asset into ~/Downloads and print where it went.
+///
+/// Progress goes to stderr, which ends up in alfred's debugger.
+pub async fn download(repo: FullName, asset: u64) -> Result<()> {
+    let octocrab = OCTOCRAB.clone();
+    let asset = octocrab
+        .repos(&repo.owner, &repo.name)
+        .releases()
+        .get_asset(AssetId(asset))
+        .await?;
+    let path = dirs::download_dir()
+        .wrap_err("no downloads directory")?
+        .join(&asset.name);
+    // the api url works for private repos too, and redirects to the file
+    let token = HubConfig::new()?.oauth_token;
+    let request = HTTP_CLIENT
+        .get(asset.url.as_str())
+        .bearer_auth(token)
+        .header(ACCEPT, "application/octet-stream");
+    let mut reported = 0;
+    let path = github_util::download(request, &path, |written, total| {
+        let Some(total) = total.filter(|total| *total > 0) else {
+            return;
+        };
+        let percent = written * 100 / total;
+        if percent >= reported + 10 || written == total {
+            reported = percent;
+            eprintln!("{name}: {percent}%", name = asset.name);
+        }
+    })
+    .await?;
+    println!("{}", path.to_string_lossy());
+
+    Ok(())
+}
+
+fn human_size(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(1536), "1.5 KB");
+        assert_eq!(human_size(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(human_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
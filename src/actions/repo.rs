@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use eyre::{ContextCompat, Result};
+use itertools::Itertools;
+use serde_json::json;
+
+use crate::{
+    actions::runs::status_icon,
+    alfred::{AuthorIcon, Icon, Item, Items, Modifier, Modifiers},
+    github_util,
+    graphql::{self, repo_hub, repo_hub::RepoHub},
+    FullName,
+};
+
+/// A hub for one repo, each item leads on to the matching action or page.
+pub async fn run(repo: FullName) -> Result<()> {
+    let variables = repo_hub::Variables {
+        owner: repo.owner.clone(),
+        name: repo.name.clone(),
+    };
+    let r = graphql::query::<RepoHub>(&variables)
+        .await?
+        .repository
+        .wrap_err(format!("{repo} not found"))?;
+    let full_name = repo.to_string();
+    let url = &r.url;
+    // items that lead to another action carry it, the rest open their arg
+    let action = |action: &str| {
+        json!({
+            "action": action,
+            "full_name": &full_name,
+            "html_url": url,
+        })
+    };
+
+    let mut items = vec![Item::builder()
+        .title(&r.name_with_owner)
+        .subtitle(r.description.clone())
+        .arg(url)
+        .icon(AuthorIcon::from_str(&repo.owner)?)
+        .variables(json!({
+            "full_name": &full_name,
+            "html_url": url,
+            "owner": &repo.owner,
+        }))
+        .build()];
+
+    if let Some(branch) = &r.default_branch_ref {
+        let state = branch
+            .target
+            .as_ref()
+            .and_then(|target| target.status_check_rollup.as_ref())
+            .map(|rollup| rollup.state.to_lowercase());
+        items.push(
+            Item::builder()
+                .title(format!(
+                    "CI on {}: {}",
+                    branch.name,
+                    state.as_deref().unwrap_or("no checks")
+                ))
+                .subtitle("View workflow runs".to_string())
+                .arg(format!("{url}/actions"))
+                .icon(status_icon(state.as_deref().unwrap_or("skipped")))
+                .variables(action("runs"))
+                .build(),
+        );
+    }
+    items.push(
+        Item::builder()
+            .title(format!(
+                "{} open pull requests",
+                r.pull_requests.total_count
+            ))
+            .subtitle("View pull requests".to_string())
+            .arg(format!("{url}/pulls"))
+            .icon(Icon::bundled("pull-request"))
+            .variables(action("pulls"))
+            .build(),
+    );
+    items.push(
+        Item::builder()
+            .title(format!("{} open issues", r.issues.total_count))
+            .subtitle("View issues".to_string())
+            .arg(format!("{url}/issues"))
+            .icon(Icon::bundled("issue"))
+            .variables(action("issues"))
+            .build(),
+    );
+    items.push(
+        Item::builder()
+            .title(format!("{} branches", r.branches.total_count))
+            .subtitle("View branches".to_string())
+            .arg(format!("{url}/branches"))
+            .icon(Icon::bundled("branch"))
+            .variables(action("branches"))
+            .build(),
+    );
+    items.push(
+        Item::builder()
+            .title("Search code")
+            .subtitle(format!("Search code in {}", r.name_with_owner))
+            .arg(format!(
+                "https://github.com/search?type=code&q=repo%3A{}",
+                r.name_with_owner
+            ))
+            .variables(action("search-code"))
+            .build(),
+    );
+    if let Some(release) = &r.latest_release {
+        let mut subtitle = "View releases".to_string();
+        if let Some(published_at) = release.published_at {
+            subtitle = format!("Published {} · {subtitle}", published_at.format("%Y-%m-%d"));
+        }
+        items.push(
+            Item::builder()
+                .title(format!(
+                    "Latest release: {}",
+                    release.name.as_deref().unwrap_or(&release.tag_name)
+                ))
+                .subtitle(subtitle)
+                .arg(&release.url)
+                .icon(Icon::bundled("release"))
+                .variables(action("releases"))
+                .build(),
+        );
+    }
+
+    let mut about = vec![format!("★ {}", r.stargazer_count)];
+    if let Some(language) = &r.primary_language {
+        about.push(language.name.clone());
+    }
+    if let Some(pushed_at) = r.pushed_at {
+        about.push(format!("pushed {}", pushed_at.format("%Y-%m-%d")));
+    }
+    items.push(
+        Item::builder()
+            .title(about.join(" · "))
+            .subtitle("Star or unstar".to_string())
+            .arg(format!("{url}/stargazers"))
+            .icon(Icon::bundled("star"))
+            .variables(action("star"))
+            .mods(
+                Modifiers::builder()
+                    .cmd(
+                        Modifier::builder()
+                            .subtitle("View stargazers")
+                            .arg(format!("{url}/stargazers"))
+                            .variables(json!({ "action": "" }))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build(),
+    );
+    let topics = r
+        .repository_topics
+        .nodes
+        .iter()
+        .map(|node| node.topic.name.as_str())
+        .collect::<Vec<_>>();
+    if !topics.is_empty() {
+        items.push(
+            Item::builder()
+                .title(format!("Topics: {}", topics.join(", ")))
+                .subtitle("Search repos with these topics".to_string())
+                .arg(format!("https://github.com/topics/{}", topics[0]))
+                .icon(Icon::bundled("tag"))
+                .variables(json!({
+                    "action": "search-repos",
+                    "query": topics.iter().map(|topic| format!("topic:{topic}")).join(" "),
+                }))
+                .mods(
+                    Modifiers::builder()
+                        .cmd(
+                            Modifier::builder()
+                                .subtitle(format!("View the {} topic", topics[0]))
+                                .arg(format!("https://github.com/topics/{}", topics[0]))
+                                .variables(json!({ "action": "" }))
+                                .build(),
+                        )
+                        .build(),
+                )
+                .build(),
+        );
+    }
+    let items = Items::from_iter(items);
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
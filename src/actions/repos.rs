@@ -1,23 +1,26 @@
-use std::{
-    path::{Path, PathBuf},
-    str::FromStr,
-    sync::Arc,
-};
+use std::{path::Path, str::FromStr};
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{Duration, Utc};
 use eyre::{ContextCompat, Result};
-use futures::Future;
+use indexmap::IndexMap;
 use itertools::Itertools;
-use octocrab::{models::Repository, Octocrab};
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tokio::fs;
 
 use crate::{
     alfred::{tokenize, AuthorIcon, Item, Items, Modifier, Modifiers, ALFRED_WORKFLOW_DATA},
-    github_util, OCTOCRAB,
+    cache::{self, Since},
+    github_util,
+    graphql::{self, viewer_repos, viewer_repos::ViewerRepos},
+    template, FullName,
 };
 
-pub async fn run(no_cache: bool) -> Result<()> {
+/// Stars are fetched again after this, repos are only ever refreshed.
+const STARS_EXPIRE: Duration = Duration::hours(1);
+
+pub async fn run(config: &ReposConfig, no_cache: bool) -> Result<()> {
     fs::create_dir_all(ALFRED_WORKFLOW_DATA.as_ref()?).await?;
 
     let both_cache = ALFRED_WORKFLOW_DATA.as_ref()?.join("both.json");
@@ -32,21 +35,14 @@ pub async fn run(no_cache: bool) -> Result<()> {
         return Ok(());
     }
 
-    let octocrab = OCTOCRAB.clone();
-    let repos =
-        cache_repos_smart(&repos_cache, |since| user_repos(octocrab.clone(), since)).await?;
-    let stars = cache_repos(stars_cache, Duration::seconds(3600), || {
-        user_starred_repos(octocrab.clone())
-    })
-    .await?;
+    let repos = cached_repos(&repos_cache, &stars_cache).await?;
 
     let items: Items = repos
         .into_iter()
-        .chain(stars.into_iter())
-        .unique_by(|r| r.full_name.clone())
-        .sorted_by(|a, b| a.full_name.cmp(&b.full_name))
-        .filter(|r| r.full_name.is_some() && r.archived == Some(false))
-        .map(repository_to_item)
+        .unique_by(|r| r.full_name.to_string())
+        .sorted_by(|a, b| a.full_name.to_string().cmp(&b.full_name.to_string()))
+        .filter(|r| !r.archived)
+        .map(|r| repository_to_item(config, r))
         .collect::<Result<_>>()?;
 
     github_util::fetch_avatars(&items).await?;
@@ -58,14 +54,145 @@ pub async fn run(no_cache: bool) -> Result<()> {
     Ok(())
 }
 
-fn repository_to_item(r: Repository) -> Result<Item> {
-    let html_url = r.html_url.clone().wrap_err("html_url is None")?.to_string();
-    let full_name = r.full_name.clone().wrap_err("full_name is None")?;
-    let name = r.name.clone();
-    let owner = r.owner.clone().wrap_err("owner is None")?.login;
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReposConfig {
+    /// what alt, cmd, ctrl, fn, shift and combinations like cmd+shift do on
+    /// repo items, either `{ url = "{html_url}/actions" }` or an action of
+    /// the workflow such as `{ action = "issues" }`, "copy", "clone" or "open-local"
+    modifiers: IndexMap<String, RepoModifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RepoModifier {
+    /// {html_url}, {full_name}, {owner} and {name} are replaced
+    Url {
+        url: String,
+        subtitle: Option<String>,
+    },
+    Action {
+        action: String,
+        subtitle: Option<String>,
+    },
+}
+
+impl Default for ReposConfig {
+    fn default() -> Self {
+        let action = |action: &str| RepoModifier::Action {
+            action: action.to_string(),
+            subtitle: None,
+        };
+        Self {
+            modifiers: IndexMap::from([
+                (
+                    "alt".to_string(),
+                    RepoModifier::Url {
+                        url: "{html_url}/wiki".to_string(),
+                        subtitle: Some("View GitHub Wiki".to_string()),
+                    },
+                ),
+                ("cmd".to_string(), action("pulls")),
+                ("ctrl".to_string(), action("clone")),
+                ("shift".to_string(), action("issues")),
+                ("fn".to_string(), action("star")),
+                ("cmd+shift".to_string(), action("runs")),
+                ("cmd+alt".to_string(), action("repo")),
+            ]),
+        }
+    }
+}
+
+/// What a repo item is made from, whether the repo came from the rest or graphql api.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoSummary {
+    full_name: FullName,
+    description: Option<String>,
+    html_url: String,
+    archived: bool,
+}
+
+impl TryFrom<Repository> for RepoSummary {
+    type Error = eyre::Report;
+
+    fn try_from(r: Repository) -> Result<Self> {
+        Ok(Self {
+            full_name: api_full_name(&r.full_name.wrap_err("full_name is None")?)?,
+            description: r.description,
+            html_url: r.html_url.wrap_err("html_url is None")?.to_string(),
+            archived: r.archived.unwrap_or_default(),
+        })
+    }
+}
+
+impl TryFrom<viewer_repos::Repository> for RepoSummary {
+    type Error = eyre::Report;
+
+    fn try_from(r: viewer_repos::Repository) -> Result<Self> {
+        Ok(Self {
+            full_name: api_full_name(&r.name_with_owner)?,
+            description: r.description,
+            html_url: r.url,
+            archived: r.is_archived,
+        })
+    }
+}
+
+/// Names from the api are taken as they are rather than validated like typed ones,
+/// github knows better which owners and names exist.
+fn api_full_name(full_name: &str) -> Result<FullName> {
+    let (owner, name) = full_name
+        .split_once('/')
+        .wrap_err(format!("{full_name} is not owner/name"))?;
+
+    Ok(FullName {
+        owner: owner.to_string(),
+        name: name.to_string(),
+    })
+}
+
+pub fn repository_to_item<R>(config: &ReposConfig, r: R) -> Result<Item>
+where
+    R: TryInto<RepoSummary>,
+    eyre::Report: From<R::Error>,
+{
+    let r = r.try_into()?;
+    let html_url = r.html_url;
+    let full_name = r.full_name.to_string();
+    let FullName { owner, name } = r.full_name;
+    let mods = config
+        .modifiers
+        .iter()
+        .map(|(key, modifier)| {
+            let modifier = match modifier {
+                RepoModifier::Url { url, subtitle } => {
+                    let url = template::render(url, |placeholder| match placeholder {
+                        "html_url" => Some(html_url.clone()),
+                        "full_name" => Some(full_name.clone()),
+                        "owner" => Some(owner.clone()),
+                        "name" => Some(name.clone()),
+                        _ => None,
+                    })?;
+                    Modifier::builder()
+                        .subtitle(subtitle.clone().unwrap_or_else(|| format!("Open {url}")))
+                        .arg(url)
+                        .build()
+                }
+                RepoModifier::Action { action, subtitle } => Modifier::builder()
+                    .subtitle(subtitle.clone().unwrap_or_else(|| action_subtitle(action)))
+                    .variables(json!({
+                        "action": action,
+                        "full_name": &full_name,
+                        "html_url": &html_url,
+                    }))
+                    .build(),
+            };
+            Ok((key.clone(), modifier))
+        })
+        .collect::<Result<Vec<_>>>()?;
     let item = Item::builder()
         .title(&full_name)
-        .subtitle(r.description.clone())
+        .subtitle(r.description)
         .uid(&html_url)
         .arg(&html_url)
         .matches(tokenize(&full_name)?.join(" "))
@@ -78,101 +205,124 @@ fn repository_to_item(r: Repository) -> Result<Item> {
                "html_url": &html_url,
            }
         ))
-        .mods(
-            Modifiers::builder()
-                .alt(
-                    Modifier::builder()
-                        .subtitle("View GitHub Wiki")
-                        .arg(format!("{html_url}/wiki"))
-                        .build(),
-                )
-                .cmd(
-                    Modifier::builder()
-                        .subtitle("View Pull Requests")
-                        .variables(json!({
-                            "action": "pulls",
-                            "full_name": &full_name,
-                            "html_url": &html_url,
-                        }))
-                        .build(),
-                )
-                .build(),
-        )
+        .mods(Modifiers::from_keys(mods)?)
         .build();
 
     Ok(item)
 }
 
-async fn user_repos(crab: Arc<Octocrab>, since: Option<Since>) -> Result<Vec<Repository>> {
-    let mut repos = crab
-        .current()
-        .list_repos_for_authenticated_user()
-        .per_page(100u8);
-    if let Some(since) = since {
-        repos = repos.since(since);
+fn action_subtitle(action: &str) -> String {
+    match action {
+        "pulls" => "View Pull Requests".to_string(),
+        "issues" => "View Issues".to_string(),
+        "runs" => "View Workflow Runs".to_string(),
+        "releases" => "View Releases".to_string(),
+        "discussions" => "View Discussions".to_string(),
+        "branches" => "View Branches".to_string(),
+        "commits" => "View Commits".to_string(),
+        "compare" => "Compare the local branch".to_string(),
+        "repo" => "Show repo overview".to_string(),
+        "star" => "Star or unstar".to_string(),
+        "copy" => "Copy link".to_string(),
+        "clone" => "Clone and open in editor".to_string(),
+        "open-local" => "Open local clone in editor".to_string(),
+        "search-code" => "Search code".to_string(),
+        action => format!("Run {action}"),
     }
-
-    Ok(crab.all_pages(repos.send().await?).await?)
-}
-
-async fn user_starred_repos(crab: Arc<Octocrab>) -> Result<Vec<Repository>> {
-    let repos = crab
-        .current()
-        .list_repos_starred_by_authenticated_user()
-        .per_page(100u8)
-        .send()
-        .await?;
-    Ok(crab.all_pages(repos).await?)
 }
 
-type Since = DateTime<Utc>;
+/// The user's repos, refreshed with the ones updated since repos.json was
+/// written, and their stars, fetched again once stars.json expires because
+/// unstarring doesn't update anything.
+///
+/// A cache file from before a change to [RepoSummary] doesn't read, so it
+/// is fetched again in full.
+async fn cached_repos(repos_cache: &Path, stars_cache: &Path) -> Result<Vec<RepoSummary>> {
+    let cached_repos = cache::read::<Vec<RepoSummary>>(repos_cache).await.ok();
+    let cached_stars = cache::read::<Vec<RepoSummary>>(stars_cache)
+        .await
+        .ok()
+        .filter(|(since, _)| Utc::now() - *since < STARS_EXPIRE);
+    let since = cached_repos.as_ref().map(|(since, _)| *since);
+    let (updated, stars) = viewer_repos(since, cached_stars.is_none()).await?;
 
-async fn cache_repos_smart<P, F, T>(file: P, fetch: F) -> Result<Vec<Repository>>
-where
-    P: AsRef<Path>,
-    F: FnOnce(Option<Since>) -> T,
-    T: Future<Output = Result<Vec<Repository>>>,
-{
-    let repos = if let Ok((since, repos)) = cached(file.as_ref()).await {
-        let new_repos = fetch(Some(since)).await?;
-        let repos = repos
-            .into_iter()
-            .chain(new_repos.into_iter())
-            .filter(|r| r.full_name.is_some() && r.archived == Some(false))
-            .collect::<Vec<_>>();
-        fs::write(file.as_ref(), serde_json::to_string(&repos)?).await?;
-        repos
-    } else {
-        fetch(None).await?
+    // updated repos come first so they win over their cached versions
+    let repos = updated
+        .into_iter()
+        .chain(cached_repos.into_iter().flat_map(|(_, repos)| repos))
+        .unique_by(|r| r.full_name.to_string())
+        .collect::<Vec<_>>();
+    cache::write(repos_cache, &repos).await?;
+    let stars = match cached_stars {
+        Some((_, stars)) => stars,
+        None => {
+            cache::write(stars_cache, &stars).await?;
+            stars
+        }
     };
 
-    Ok(repos)
+    Ok(repos.into_iter().chain(stars).collect())
 }
 
-async fn cache_repos<P, F, T>(file: P, expires: Duration, fetch: F) -> Result<Vec<Repository>>
-where
-    P: AsRef<Path>,
-    F: FnOnce() -> T,
-    T: Future<Output = Result<Vec<Repository>>>,
-{
-    match cached(file.as_ref()).await {
-        Ok((since, repos)) if Utc::now() - since < expires => Ok(repos),
-        _ => {
-            let repos = fetch().await?;
-            fs::write(file.as_ref(), serde_json::to_string(&repos)?).await?;
-
-            Ok(repos)
+/// The user's repos updated since `since`, or all of them, and their stars
+/// when `with_stars`, paged through together so that each page of both
+/// costs one request.
+async fn viewer_repos(
+    since: Option<Since>,
+    with_stars: bool,
+) -> Result<(Vec<RepoSummary>, Vec<RepoSummary>)> {
+    let mut variables = viewer_repos::Variables {
+        repos_after: None,
+        stars_after: None,
+        with_repos: true,
+        with_stars,
+    };
+    let mut repos = vec![];
+    let mut stars = vec![];
+    while variables.with_repos || variables.with_stars {
+        let viewer = graphql::query::<ViewerRepos>(&variables).await?.viewer;
+        if variables.with_repos {
+            let connection = viewer
+                .repositories
+                .wrap_err("viewer.repositories is None")?;
+            // newest first, so the rest are older than the cache once one is
+            let done = match (since, connection.nodes.last()) {
+                (Some(since), Some(oldest)) => oldest.updated_at < since,
+                _ => false,
+            };
+            variables.repos_after = connection.next_cursor().filter(|_| !done);
+            variables.with_repos = variables.repos_after.is_some();
+            repos.extend(connection.nodes);
+        }
+        if variables.with_stars {
+            let connection = viewer
+                .starred_repositories
+                .wrap_err("viewer.starredRepositories is None")?;
+            variables.stars_after = connection.next_cursor();
+            variables.with_stars = variables.stars_after.is_some();
+            stars.extend(connection.nodes);
         }
     }
+    let summarize = |repos: Vec<viewer_repos::Repository>| {
+        repos
+            .into_iter()
+            .map(RepoSummary::try_from)
+            .collect::<Result<Vec<_>>>()
+    };
+
+    Ok((summarize(repos)?, summarize(stars)?))
 }
 
-async fn cached<P>(file: P) -> Result<(Since, Vec<Repository>)>
-where
-    P: AsRef<Path>,
-{
-    let repos = fs::read_to_string(file.as_ref()).await?;
-    let repos: Vec<Repository> = serde_json::from_str(&repos)?;
-    let since = tokio::fs::metadata(file.as_ref()).await?.modified()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok((DateTime::from(since), repos))
+    #[test]
+    fn test_partial_config() {
+        let config: ReposConfig = toml::from_str("").unwrap();
+        assert_eq!(
+            config.modifiers.keys().collect::<Vec<_>>(),
+            ReposConfig::default().modifiers.keys().collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Duration, Utc};
+use eyre::{ContextCompat, Result};
+use octocrab::models::{
+    workflows::{Conclusion, Run},
+    RunId,
+};
+use serde_json::json;
+
+use crate::{
+    alfred::{Icon, Item, Items, Modifier, Modifiers},
+    args::RunMethod,
+    FullName, OCTOCRAB,
+};
+
+/// Recent workflow runs for a repo.
+pub async fn run(repo: FullName) -> Result<()> {
+    let runs = OCTOCRAB
+        .workflows(&repo.owner, &repo.name)
+        .list_all_runs()
+        .page(1u32)
+        .per_page(15u8)
+        .send()
+        .await?;
+    let items: Items = runs
+        .into_iter()
+        .map(|run| run_to_item(&repo, run))
+        .collect();
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+pub async fn update(method: RunMethod) -> Result<()> {
+    let octocrab = OCTOCRAB.clone();
+    match method {
+        RunMethod::Rerun { repo, run } => {
+            let route = format!("/repos/{repo}/actions/runs/{run}/rerun-failed-jobs");
+            octocrab::map_github_error(octocrab._post(route, None::<&()>).await?).await?;
+            println!("Re-running failed jobs");
+        }
+        RunMethod::Cancel { repo, run } => {
+            let route = format!("/repos/{repo}/actions/runs/{run}/cancel");
+            octocrab::map_github_error(octocrab._post(route, None::<&()>).await?).await?;
+            println!("Cancelled");
+        }
+        RunMethod::Logs { repo, run } => {
+            // the logs are on the page of each job, so open the one that failed
+            let jobs = octocrab
+                .workflows(&repo.owner, &repo.name)
+                .list_jobs(RunId(run))
+                .send()
+                .await?
+                .items;
+            let job = jobs
+                .iter()
+                .find(|job| matches!(job.conclusion, Some(Conclusion::Failure)))
+                .or(jobs.first())
+                .wrap_err("run has no jobs")?;
+            open::that(job.html_url.as_str())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Conclusions of runs that failed, which are the ones with failed jobs to re-run.
+const FAILURES: [&str; 3] = ["failure", "timed_out", "startup_failure"];
+
+/// The icon name for a status or conclusion of a run or check.
+pub fn status_icon(status: &str) -> Icon {
+    Icon::bundled(match status.to_lowercase().as_str() {
+        "success" => "status-success",
+        // error is the state of a status check rollup
+        status if FAILURES.contains(&status) || status == "error" => "status-failure",
+        "cancelled" => "status-cancelled",
+        "skipped" | "neutral" | "stale" => "status-skipped",
+        _ => "status-pending",
+    })
+}
+
+fn run_to_item(repo: &FullName, run: Run) -> Item {
+    let completed = run.status == "completed";
+    let status = match &run.conclusion {
+        Some(conclusion) if completed => conclusion.clone(),
+        _ => run.status.clone(),
+    };
+    let ended_at = if completed {
+        run.updated_at
+    } else {
+        Utc::now()
+    };
+    let html_url = run.html_url.to_string();
+    let modifier = |subtitle: &str, method: &str, valid: bool| {
+        Modifier::builder()
+            .subtitle(subtitle)
+            .valid(valid)
+            .variables(json!({
+                "action": "run",
+                "method": method,
+                "full_name": repo.to_string(),
+                "run": run.id.0,
+            }))
+            .build()
+    };
+
+    Item::builder()
+        .title(format!(
+            "{name} #{number}: {message}",
+            name = run.name,
+            number = run.run_number,
+            message = run.head_commit.message.lines().next().unwrap_or_default(),
+        ))
+        .subtitle(format!(
+            "{status} · {branch} · {event} · {duration}",
+            status = status.replace('_', " "),
+            branch = run.head_branch,
+            event = run.event,
+            duration = duration(run.created_at, ended_at),
+        ))
+        .uid(run.id.0.to_string())
+        .arg(&html_url)
+        .icon(status_icon(&status))
+        .variables(json!({
+            "created_at": run.created_at,
+            "updated_at": run.updated_at,
+            "full_name": repo.to_string(),
+            "html_url": html_url,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(modifier(
+                    "Re-run failed jobs",
+                    "rerun",
+                    completed && FAILURES.contains(&status.as_str()),
+                ))
+                .alt(modifier("Cancel run", "cancel", !completed))
+                .ctrl(modifier("Open logs", "logs", true))
+                .build(),
+        )
+        .build()
+}
+
+fn duration(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    let duration = (end - start).max(Duration::zero());
+    match (
+        duration.num_hours(),
+        duration.num_minutes() % 60,
+        duration.num_seconds() % 60,
+    ) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, m, _) => format!("{h}h {m}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    fn rerun_valid(status: &str, conclusion: Option<&str>) -> Value {
+        let mut run: Run =
+            serde_json::from_str(include_str!("../../tests/fixtures/workflow_run.json")).unwrap();
+        run.status = status.to_string();
+        run.conclusion = conclusion.map(str::to_string);
+        let repo = "artichoke/artichoke".parse().unwrap();
+        let item = serde_json::to_value(run_to_item(&repo, run)).unwrap();
+
+        item["mods"]["cmd"]["valid"].clone()
+    }
+
+    #[test]
+    fn test_rerun() {
+        assert_eq!(rerun_valid("queued", None), json!(false));
+        assert_eq!(rerun_valid("completed", Some("success")), json!(false));
+        assert_eq!(rerun_valid("completed", Some("cancelled")), json!(false));
+        for conclusion in FAILURES {
+            assert_eq!(rerun_valid("completed", Some(conclusion)), json!(true));
+        }
+    }
+
+    #[test]
+    fn test_duration() {
+        let start = Utc::now();
+        assert_eq!(duration(start, start + Duration::seconds(42)), "42s");
+        assert_eq!(duration(start, start + Duration::seconds(185)), "3m 5s");
+        assert_eq!(duration(start, start + Duration::minutes(125)), "2h 5m");
+        // clock skew shouldn't produce negative durations
+        assert_eq!(duration(start, start - Duration::seconds(3)), "0s");
+    }
+}
@@ -0,0 +1,208 @@
+use std::str::FromStr;
+
+use eyre::Result;
+use futures::{stream, StreamExt, TryStreamExt};
+use octocrab::Page;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    alfred::{AuthorIcon, Item, Items, Modifier, Modifiers},
+    github_util, FullName, OCTOCRAB,
+};
+
+/// How many files are fetched at once to find the line numbers of matches.
+const BLOB_CONCURRENCY: usize = 5;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchCodeConfig {
+    /// searches are limited to this org unless a repo is given
+    org: Option<String>,
+
+    /// link to the matched line, which costs a request per result
+    line_numbers: bool,
+}
+
+impl Default for SearchCodeConfig {
+    fn default() -> Self {
+        Self {
+            org: None,
+            line_numbers: true,
+        }
+    }
+}
+
+/// The parts of a code search result we use, octocrab's Code lacks text_matches.
+#[derive(Debug, Deserialize)]
+struct CodeResult {
+    path: String,
+    sha: String,
+    html_url: String,
+    repository: CodeRepository,
+    #[serde(default)]
+    text_matches: Vec<TextMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeRepository {
+    full_name: String,
+    owner: CodeOwner,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeOwner {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextMatch {
+    fragment: String,
+    matches: Vec<Match>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Match {
+    indices: [usize; 2],
+}
+
+pub async fn run(config: &SearchCodeConfig, query: String, repo: Option<FullName>) -> Result<()> {
+    let query = match (repo, &config.org) {
+        (Some(repo), _) => format!("{query} repo:{repo}"),
+        (None, Some(org)) => format!("{query} org:{org}"),
+        (None, None) => query,
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        ACCEPT,
+        HeaderValue::from_static("application/vnd.github.text-match+json"),
+    );
+    let results: Page<CodeResult> = OCTOCRAB
+        .get_with_headers(
+            "/search/code",
+            Some(&json!({ "q": query, "per_page": 15 })),
+            Some(headers),
+        )
+        .await?;
+    let items: Vec<Item> = stream::iter(results)
+        .map(|code| code_to_item(code, config.line_numbers))
+        .buffered(BLOB_CONCURRENCY)
+        .try_collect()
+        .await?;
+    let items = Items::from_iter(items);
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+async fn code_to_item(code: CodeResult, line_numbers: bool) -> Result<Item> {
+    let full_name = &code.repository.full_name;
+    let owner = &code.repository.owner.login;
+    let text_match = code
+        .text_matches
+        .first()
+        .and_then(|tm| Some((tm, tm.matches.first()?.indices[0])));
+    let fragment = text_match
+        .map(|(tm, index)| matched_line(&tm.fragment, index))
+        .unwrap_or_default();
+    // the search api doesn't say which line matched, so find the fragment in the file,
+    // when that fails the link is to the whole file
+    let line = match text_match {
+        Some((tm, index)) if line_numbers => blob(full_name, &code.sha)
+            .await
+            .ok()
+            .and_then(|content| line_number(&content, &tm.fragment, index)),
+        _ => None,
+    };
+    // html_url is at a specific commit, so it doubles as a permalink
+    let permalink = match line {
+        Some(line) => format!("{}#L{line}", code.html_url),
+        None => code.html_url.clone(),
+    };
+
+    let item = Item::builder()
+        .title(&code.path)
+        .subtitle(format!("{full_name}: {fragment}"))
+        .arg(&permalink)
+        .icon(AuthorIcon::from_str(owner)?)
+        .variables(json!({
+            "full_name": format!("{full_name}/{path}", path = code.path),
+            "html_url": &permalink,
+            "owner": owner,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(
+                    Modifier::builder()
+                        .subtitle("Copy permalink")
+                        .arg(&permalink)
+                        .variables(json!({ "action": "clipboard" }))
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    Ok(item)
+}
+
+async fn blob(full_name: &str, sha: &str) -> Result<String> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        ACCEPT,
+        HeaderValue::from_static("application/vnd.github.raw"),
+    );
+    let res = OCTOCRAB
+        ._get_with_headers(format!("/repos/{full_name}/git/blobs/{sha}"), Some(headers))
+        .await?;
+    let res = octocrab::map_github_error(res).await?;
+
+    Ok(OCTOCRAB.body_to_string(res).await?)
+}
+
+/// The line of `fragment` that contains `index`, trimmed.
+fn matched_line(fragment: &str, index: usize) -> String {
+    let index = index.min(fragment.len());
+    let start = fragment
+        .get(..index)
+        .and_then(|s| s.rfind('\n'))
+        .map_or(0, |i| i + 1);
+    let end = fragment
+        .get(index..)
+        .and_then(|s| s.find('\n'))
+        .map_or(fragment.len(), |i| i + index);
+
+    fragment
+        .get(start..end)
+        .unwrap_or(fragment)
+        .trim()
+        .to_string()
+}
+
+/// The 1-based line number of `index` within `fragment` within `content`.
+fn line_number(content: &str, fragment: &str, index: usize) -> Option<usize> {
+    let offset = content.find(fragment)? + index;
+
+    Some(content.get(..offset)?.matches('\n').count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let content = "one\ntwo\nthree four\nfive\n";
+        let fragment = "two\nthree four\nfive";
+        let index = fragment.find("four").unwrap();
+
+        assert_eq!(matched_line(fragment, index), "three four");
+        assert_eq!(line_number(content, fragment, index), Some(3));
+        assert_eq!(line_number(content, "six", 0), None);
+    }
+}
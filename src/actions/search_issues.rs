@@ -1,51 +1,207 @@
 use eyre::{ContextCompat, Result};
+use indexmap::IndexMap;
 use octocrab::models::issues::Issue;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::json;
 
 use crate::{
+    actions::pulls,
     alfred::{AuthorIcon, Item, Items},
-    args::SearchQuery,
-    github_util, OCTOCRAB,
+    args::{SearchOptions, SearchQuery},
+    github_util,
+    hub_compat::HubConfig,
+    template, FullName, OCTOCRAB,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchIssuesConfig {
-    reviews: String,
-    pulls: String,
+    /// Extra placeholders for queries, e.g. org = "mozilla" for {org}
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    variables: IndexMap<String, String>,
+
+    /// Saved searches by name
+    #[serde(flatten, deserialize_with = "saved_searches")]
+    searches: IndexMap<String, SavedSearch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    query: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+
+    #[serde(default = "default_sort")]
+    sort: String,
+
+    /// Results per page, at most 100
+    #[serde(default = "default_limit")]
+    limit: u8,
+
+    /// How many pages to fetch before showing more results
+    #[serde(default = "default_pages")]
+    pages: u32,
+
+    /// Whose avatar to show: the author of the issue or the owner of the repo
+    #[serde(default)]
+    icon: SearchIcon,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchIcon {
+    #[default]
+    Author,
+    Owner,
+}
+
+impl SavedSearch {
+    fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            title: None,
+            sort: default_sort(),
+            limit: default_limit(),
+            pages: default_pages(),
+            icon: SearchIcon::default(),
+        }
+    }
+}
+
+fn default_sort() -> String {
+    "updated".to_string()
+}
+
+fn default_limit() -> u8 {
+    15
+}
+
+fn default_pages() -> u32 {
+    1
+}
+
+/// Saved searches used to be plain query strings, which are still accepted.
+/// The old `reviews` and `pulls` keep the title and icon they had.
+fn saved_searches<'de, D>(deserializer: D) -> Result<IndexMap<String, SavedSearch>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum QueryOrSearch {
+        Query(String),
+        Search(SavedSearch),
+    }
+
+    let mut defaults = SearchIssuesConfig::default().searches;
+    let searches = IndexMap::<String, QueryOrSearch>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(name, search)| match search {
+            QueryOrSearch::Query(query) => {
+                let search = match defaults.swap_remove(&name) {
+                    Some(search) => SavedSearch { query, ..search },
+                    None => SavedSearch::new(query),
+                };
+                (name, search)
+            }
+            QueryOrSearch::Search(search) => (name, search),
+        })
+        .collect();
+
+    Ok(searches)
 }
 
 impl Default for SearchIssuesConfig {
     fn default() -> Self {
+        let reviews = SavedSearch {
+            title: Some("Review requests".to_string()),
+            ..SavedSearch::new("is:open is:pr user-review-requested:@me archived:false")
+        };
+        let pulls = SavedSearch {
+            title: Some("My pull requests".to_string()),
+            icon: SearchIcon::Owner,
+            ..SavedSearch::new("is:open is:pr archived:false author:@me")
+        };
+
         Self {
-            reviews: "is:open is:pr user-review-requested:@me archived:false".to_string(),
-            pulls: "is:open is:pr archived:false author:@me".to_string(),
+            variables: IndexMap::new(),
+            searches: IndexMap::from([
+                ("reviews".to_string(), reviews),
+                ("pulls".to_string(), pulls),
+            ]),
         }
     }
 }
 
 pub async fn run(config: &SearchIssuesConfig, query: SearchQuery) -> Result<()> {
-    let full_name_re = Regex::new(r"^https://github.com/(?<full_name>[^/]+/[^/]+)")?;
-
-    let query_str = match query {
-        SearchQuery::Reviews => &config.reviews,
-        SearchQuery::Pulls => &config.pulls,
-        SearchQuery::Custom { ref query } => query,
+    let (search, options, mut rerun) = match query {
+        SearchQuery::List => return list(config),
+        SearchQuery::Saved { name, options } => {
+            let search = config
+                .searches
+                .get(&name)
+                .cloned()
+                .wrap_err(format!("no saved search named {name}"))?;
+            (search, options, json!({ "search": name }))
+        }
+        SearchQuery::Custom { query, options } => {
+            let rerun = json!({ "query": &query });
+            (SavedSearch::new(query), options, rerun)
+        }
     };
+    let query = expand_query(config, &search.query, &options)?;
+    let full_name_re = Regex::new(r"^https://github.com/(?<full_name>[^/]+/[^/]+)")?;
 
-    let items: Items = OCTOCRAB
-        .clone()
+    let octocrab = OCTOCRAB.clone();
+    let mut page = octocrab
         .search()
-        .issues_and_pull_requests(&query_str)
-        .page(1u8)
-        .per_page(15u8)
-        .sort("updated")
+        .issues_and_pull_requests(&query)
+        .page(options.page)
+        .per_page(search.limit.min(100))
+        .sort(&search.sort)
         .send()
-        .await?
-        .into_iter()
-        .map(|issue| issue_to_item(issue, &query, &full_name_re))
-        .collect::<Result<_>>()?;
+        .await?;
+    let total_count = page.total_count;
+    let mut issues = page.take_items();
+    let mut pages = 1;
+    while pages < search.pages {
+        match octocrab.get_page(&page.next).await? {
+            Some(next) => page = next,
+            None => break,
+        }
+        issues.extend(page.take_items());
+        pages += 1;
+    }
+
+    let mut items = vec![];
+    if let Some(total_count) = total_count {
+        items.push(
+            Item::builder()
+                .title(format!("{total_count} results"))
+                .subtitle(search.title.clone().unwrap_or_else(|| query.clone()))
+                .valid(false)
+                .build(),
+        );
+    }
+    for issue in issues {
+        items.push(issue_to_item(issue, search.icon, &full_name_re)?);
+    }
+    if page.next.is_some() {
+        let next = options.page + pages;
+        rerun["action"] = json!("search-issues");
+        rerun["input"] = json!(options.input);
+        rerun["repo"] = json!(options.repo);
+        rerun["page"] = json!(next);
+        items.push(
+            Item::builder()
+                .title("More results…")
+                .subtitle(format!("Show page {next}"))
+                .variables(rerun)
+                .build(),
+        );
+    }
+    let items = Items::from_iter(items);
 
     github_util::fetch_avatars(&items).await?;
 
@@ -55,7 +211,62 @@ pub async fn run(config: &SearchIssuesConfig, query: SearchQuery) -> Result<()>
     Ok(())
 }
 
-fn issue_to_item(issue: Issue, query: &SearchQuery, full_name_re: &Regex) -> Result<Item> {
+/// Expand placeholders in the query:
+///
+/// - `{me}` is the hub user
+/// - `{repo}` is the repo passed with --repo
+/// - `{input}` is the text typed in alfred
+/// - `{today}`, `{today-7d}` and so on are dates
+/// - anything else comes from the configured variables
+fn expand_query(
+    config: &SearchIssuesConfig,
+    query: &str,
+    options: &SearchOptions,
+) -> Result<String> {
+    let mut me = None;
+    let today = chrono::Local::now().date_naive();
+    template::render(query, |name| match name {
+        "me" => {
+            if me.is_none() {
+                me = HubConfig::new().ok().map(|hc| hc.user);
+            }
+            me.clone()
+        }
+        "repo" => options.repo.as_ref().map(|repo| repo.to_string()),
+        "input" => Some(options.input.clone().unwrap_or_default()),
+        name => {
+            template::relative_date(name, today).or_else(|| config.variables.get(name).cloned())
+        }
+    })
+    .map(|query| query.trim().to_string())
+}
+
+/// Every saved search as an item, selecting one runs it.
+fn list(config: &SearchIssuesConfig) -> Result<()> {
+    let items: Items = config
+        .searches
+        .iter()
+        .map(|(name, search)| {
+            Item::builder()
+                .title(search.title.clone().unwrap_or_else(|| name.clone()))
+                .subtitle(search.query.clone())
+                .uid(name)
+                .arg(name)
+                .variables(json!({
+                    "action": "search-issues",
+                    "search": name,
+                }))
+                .build()
+        })
+        .collect();
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+fn issue_to_item(issue: Issue, icon: SearchIcon, full_name_re: &Regex) -> Result<Item> {
     let html_url = issue.html_url.clone().to_string();
     let caps = full_name_re
         .captures(&html_url)
@@ -65,15 +276,24 @@ fn issue_to_item(issue: Issue, query: &SearchQuery, full_name_re: &Regex) -> Res
         full_name = &caps["full_name"],
         number = issue.number
     );
-    let owner = match query {
-        SearchQuery::Pulls => {
+    let owner = match icon {
+        SearchIcon::Owner => {
             // user or organization of the repo url
             caps["full_name"].split_once('/').unwrap().0
         }
-        _ => &issue.user.login,
+        SearchIcon::Author => &issue.user.login,
     };
 
     let icon: AuthorIcon = owner.parse()?;
+    // pull requests in results, e.g. review requests, can be approved and merged from here
+    let mods = if issue.pull_request.is_some() {
+        let repo = caps["full_name"]
+            .parse::<FullName>()
+            .map_err(|err| eyre::eyre!(err))?;
+        Some(pulls::pull_modifiers(&repo, issue.number))
+    } else {
+        None
+    };
 
     let items = Item::builder()
         .title(issue.title.clone())
@@ -93,7 +313,55 @@ fn issue_to_item(issue: Issue, query: &SearchQuery, full_name_re: &Regex) -> Res
                 "owner": owner,
             }
         ))
+        .mods(mods)
         .build();
 
     Ok(items)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_query_config() {
+        let config: SearchIssuesConfig = toml::from_str(
+            r#"
+            reviews = "is:open is:pr user-review-requested:@me"
+            pulls = "is:open is:pr author:@me"
+
+            [triage]
+            query = "is:open is:issue no:label"
+            title = "Team triage queue"
+            icon = "owner"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.searches["reviews"].query,
+            "is:open is:pr user-review-requested:@me"
+        );
+        assert_eq!(config.searches["reviews"].limit, 15);
+        assert!(matches!(config.searches["pulls"].icon, SearchIcon::Owner));
+        assert_eq!(
+            config.searches["pulls"].title.as_deref(),
+            Some("My pull requests")
+        );
+        assert_eq!(
+            config.searches["triage"].title.as_deref(),
+            Some("Team triage queue")
+        );
+    }
+
+    #[test]
+    fn test_default_config_round_trip() {
+        let config = toml::to_string_pretty(&SearchIssuesConfig::default()).unwrap();
+        let config: SearchIssuesConfig = toml::from_str(&config).unwrap();
+
+        assert_eq!(
+            config.searches.keys().collect::<Vec<_>>(),
+            vec!["reviews", "pulls"]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use eyre::Result;
+
+use crate::{
+    actions::repos::{repository_to_item, ReposConfig},
+    alfred::Items,
+    github_util, FullName, OCTOCRAB,
+};
+
+pub async fn run(config: &ReposConfig, query: String) -> Result<()> {
+    let items: Items = OCTOCRAB
+        .clone()
+        .search()
+        .repositories(&query)
+        .page(1u8)
+        .per_page(15u8)
+        .send()
+        .await?
+        .into_iter()
+        .filter(|r| r.full_name.is_some())
+        .map(|r| repository_to_item(config, r))
+        .collect::<Result<_>>()?;
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+/// Star the repo, or unstar it if it is already starred.
+pub async fn toggle_star(repo: FullName) -> Result<()> {
+    let octocrab = OCTOCRAB.clone();
+    let route = format!("/user/starred/{repo}");
+    // 204 when starred, 404 when not
+    let starred = octocrab._get(route.as_str()).await?.status().is_success();
+    if starred {
+        octocrab::map_github_error(octocrab._delete(route.as_str(), None::<&()>).await?).await?;
+        println!("Unstarred {repo}");
+    } else {
+        octocrab::map_github_error(octocrab._put(route.as_str(), None::<&()>).await?).await?;
+        println!("Starred {repo}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use eyre::Result;
+use serde_json::json;
+
+use crate::{
+    alfred::{AuthorIcon, Item, Items, Modifier, Modifiers},
+    github_util,
+    graphql::{
+        self,
+        search_users::{self, Account, SearchUsers},
+    },
+};
+
+/// Users and organizations, searched with graphql so their names come along
+/// without fetching each profile.
+pub async fn run(query: String) -> Result<()> {
+    let accounts = graphql::query::<SearchUsers>(&search_users::Variables { query })
+        .await?
+        .search
+        .nodes;
+    let items = accounts
+        .into_iter()
+        .map(account_to_item)
+        .collect::<Result<Items>>()?;
+
+    github_util::fetch_avatars(&items).await?;
+
+    let json = serde_json::to_string(&items)?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+fn account_to_item(account: Account) -> Result<Item> {
+    let login = &account.login;
+    let html_url = &account.url;
+    let subtitle = [
+        Some(format!("@{login}")),
+        account.company.clone(),
+        account.description.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|s| !s.is_empty())
+    .collect::<Vec<_>>()
+    .join(" · ");
+    let repos_url = match account.typename.as_str() {
+        "Organization" => format!("https://github.com/orgs/{login}/repositories"),
+        _ => format!("{html_url}?tab=repositories"),
+    };
+
+    let item = Item::builder()
+        .title(
+            account
+                .name
+                .clone()
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| login.clone()),
+        )
+        .subtitle(subtitle)
+        .uid(html_url)
+        .arg(html_url)
+        .icon(AuthorIcon::from_str(login)?)
+        .variables(json!({
+            "full_name": login,
+            "html_url": html_url,
+            "owner": login,
+        }))
+        .mods(
+            Modifiers::builder()
+                .cmd(
+                    Modifier::builder()
+                        .subtitle("View Repositories")
+                        .arg(repos_url)
+                        .build(),
+                )
+                .alt(
+                    Modifier::builder()
+                        .subtitle("View Open Pull Requests")
+                        .variables(json!({
+                            "action": "search-issues",
+                            "query": format!("is:open is:pr archived:false author:{login}"),
+                        }))
+                        .build(),
+                )
+                .ctrl(
+                    Modifier::builder()
+                        .subtitle(format!("Copy @{login}"))
+                        .arg(format!("@{login}"))
+                        .variables(json!({ "action": "clipboard" }))
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    Ok(item)
+}
@@ -1,3 +1,4 @@
+use indexmap::IndexMap;
 use itertools::Itertools;
 use octocrab::models::Author;
 use serde::{Deserialize, Serialize};
@@ -40,6 +41,10 @@ pub struct Item {
 
     arg: String,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(setter(strip_option))]
+    valid: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     html_url: Option<String>,
 
@@ -59,12 +64,43 @@ pub struct Item {
     mods: Option<Modifiers>,
 }
 
+/// What a run script action prints to pass on an arg and variables.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Output {
+    alfredworkflow: AlfredWorkflow,
+}
+
+#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)), build_method(into = Output))]
+pub struct AlfredWorkflow {
+    arg: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(setter(strip_option))]
+    variables: Option<Value>,
+}
+
+impl From<AlfredWorkflow> for Output {
+    fn from(alfredworkflow: AlfredWorkflow) -> Self {
+        Self { alfredworkflow }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, TypedBuilder)]
 #[builder(field_defaults(default, setter(into)))]
 pub struct Icon {
     path: PathBuf,
 }
 
+impl Icon {
+    /// One of the icons in the workflow's icons directory.
+    pub fn bundled(name: &str) -> Self {
+        Self {
+            path: PathBuf::from(format!("icons/{name}.png")),
+        }
+    }
+}
+
 impl FromStr for AuthorIcon {
     type Err = eyre::Error;
 
@@ -138,6 +174,40 @@ pub struct Modifiers {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     shift: Option<Modifier>,
+
+    /// Combinations of keys, such as "cmd+shift"
+    #[serde(flatten)]
+    #[builder(setter(!strip_option))]
+    combinations: IndexMap<String, Modifier>,
+}
+
+static MODIFIER_KEYS: &[&str] = &["alt", "cmd", "ctrl", "fn", "shift"];
+
+impl Modifiers {
+    /// Modifiers keyed by alt, cmd, ctrl, fn, shift or a combination such as cmd+shift.
+    pub fn from_keys<I>(keys: I) -> eyre::Result<Self>
+    where
+        I: IntoIterator<Item = (String, Modifier)>,
+    {
+        let mut modifiers = Self::builder().build();
+        for (key, modifier) in keys {
+            let slot = match key.as_str() {
+                "alt" => &mut modifiers.alt,
+                "cmd" => &mut modifiers.cmd,
+                "ctrl" => &mut modifiers.ctrl,
+                "fn" => &mut modifiers.fun,
+                "shift" => &mut modifiers.shift,
+                _ if key.split('+').all(|k| MODIFIER_KEYS.contains(&k)) => {
+                    modifiers.combinations.insert(key, modifier);
+                    continue;
+                }
+                _ => return Err(eyre::eyre!("unknown modifier key {key}")),
+            };
+            *slot = Some(modifier);
+        }
+
+        Ok(modifiers)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, TypedBuilder)]
@@ -146,6 +216,9 @@ pub struct Modifier {
     #[serde(skip_serializing_if = "Option::is_none")]
     arg: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    valid: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     subtitle: Option<String>,
 
@@ -180,6 +253,7 @@ mod tests {
             subtitle: None,
             uid: None,
             arg: "foo".into(),
+            valid: None,
             html_url: None,
             matches: None,
             icon: None,
@@ -200,6 +274,7 @@ mod tests {
             subtitle: None,
             uid: None,
             arg: "foo".into(),
+            valid: None,
             html_url: None,
             matches: None,
             icon: None,
@@ -207,6 +282,7 @@ mod tests {
             mods: Some(Modifiers {
                 alt: Some(Modifier {
                     arg: None,
+                    valid: None,
                     subtitle: Some("foo".into()),
                     variables: None,
                 }),
@@ -214,6 +290,7 @@ mod tests {
                 ctrl: None,
                 fun: None,
                 shift: None,
+                combinations: IndexMap::new(),
             }),
         };
 
@@ -222,4 +299,34 @@ mod tests {
             r#"{"title":"foo","arg":"foo","mods":{"alt":{"subtitle":"foo"}}}"#
         );
     }
+
+    #[test]
+    fn test_modifiers_from_keys() {
+        let modifier = |subtitle: &str| Modifier::builder().subtitle(subtitle).build();
+        let modifiers = Modifiers::from_keys([
+            ("fn".to_string(), modifier("a")),
+            ("cmd+shift".to_string(), modifier("b")),
+        ])
+        .unwrap();
+        assert_eq!(
+            serde_json::to_string(&modifiers).unwrap(),
+            r#"{"fn":{"subtitle":"a"},"cmd+shift":{"subtitle":"b"}}"#
+        );
+
+        assert!(Modifiers::from_keys([("hyper".to_string(), modifier("c"))]).is_err());
+        assert!(Modifiers::from_keys([("cmd+".to_string(), modifier("c"))]).is_err());
+    }
+
+    #[test]
+    fn test_output() {
+        let output: Output = AlfredWorkflow::builder()
+            .arg("foo")
+            .variables(json!({"bar": "baz"}))
+            .build();
+
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"alfredworkflow":{"arg":"foo","variables":{"bar":"baz"}}}"#
+        );
+    }
 }
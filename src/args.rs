@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use eyre::Result;
 
 #[derive(Clone, Debug, Parser)]
@@ -27,24 +27,313 @@ pub enum Action {
         no_cache: bool,
     },
 
-    /// List pull requests for a repo
+    /// List pull requests for a repo, given as owner/name or any github url
     Pulls { repo: crate::FullName },
 
+    /// List issues for a repo
+    Issues {
+        repo: crate::FullName,
+
+        #[clap(flatten)]
+        filter: IssueFilter,
+    },
+
+    /// Create issues
+    Issue {
+        #[clap(subcommand)]
+        method: IssueMethod,
+    },
+
+    /// List unread notifications
+    Notifications,
+
+    /// Mark a notification thread read or done, or unsubscribe from it
+    Notification {
+        #[clap(subcommand)]
+        method: NotificationMethod,
+    },
+
+    /// List recent activity of the people and repos you follow
+    Feed {
+        #[clap(long, default_value = "false")]
+        no_cache: bool,
+    },
+
+    /// List open security alerts of the configured repos and orgs
+    Alerts,
+
+    /// List discussions of a repo, or search them
+    Discussions {
+        repo: crate::FullName,
+        query: Option<String>,
+    },
+
+    /// List your gists, filtered by query
+    Gists { query: Option<String> },
+
+    /// Create a gist
+    Gist {
+        #[clap(subcommand)]
+        method: GistMethod,
+    },
+
+    /// Approve, request changes on or merge a pull request
+    Pull {
+        #[clap(subcommand)]
+        method: PullMethod,
+    },
+
+    /// List your and your orgs' projects
+    Projects,
+
+    /// List the items of a project by status
+    ProjectItems { project: String },
+
+    /// Move a project item to another status
+    Project {
+        #[clap(subcommand)]
+        method: ProjectMethod,
+    },
+
+    /// Show a hub for a repo with counts, CI status and its latest release
+    Repo { repo: crate::FullName },
+
+    /// List branches of a repo, compared with its default branch
+    Branches { repo: crate::FullName },
+
+    /// List recent commits of a repo, given as owner/name or owner/name@branch
+    Commits { repo: crate::reference::RepoBranch },
+
+    /// Compare a branch with the default branch, owner/name alone uses the local clone's branch
+    Compare { repo: crate::reference::RepoBranch },
+
+    /// Open a pull request from a branch, titled after its commits
+    CreatePull { repo: crate::reference::RepoBranch },
+
+    /// List releases and tags of a repo
+    Releases { repo: crate::FullName },
+
+    /// List the assets of a release
+    Assets { repo: crate::FullName, release: u64 },
+
+    /// Download a release asset into ~/Downloads
+    Download { repo: crate::FullName, asset: u64 },
+
+    /// List recent workflow runs for a repo
+    Runs { repo: crate::FullName },
+
+    /// Re-run, cancel or open the logs of a workflow run
+    Run {
+        #[clap(subcommand)]
+        method: RunMethod,
+    },
+
     /// Search issues and pull requests
     SearchIssues {
         #[clap(subcommand)]
         query: SearchQuery,
     },
 
+    /// Search all of github for repos
+    SearchRepos { query: String },
+
+    /// Search code, in the configured org unless a repo is given
+    SearchCode {
+        query: String,
+
+        #[clap(long)]
+        repo: Option<crate::FullName>,
+    },
+
+    /// Search for users and organizations
+    Users { query: String },
+
+    /// Star a repo, or unstar it if it is already starred
+    Star { repo: crate::FullName },
+
     /// Copy repo info to the clipboard using mdcopy
     Copy,
+
+    /// Open whatever a pasted url, owner/name#123, #123 or commit sha refers to
+    OpenReference {
+        input: String,
+
+        /// Resolve #123 and commit shas against this repo instead of the browser's
+        #[clap(long)]
+        repo: Option<crate::FullName>,
+    },
+
+    /// Clone a repo and open it in the editor
+    Clone {
+        repo: crate::FullName,
+
+        /// Clone from this url instead of github.com
+        #[clap(long)]
+        url: Option<String>,
+    },
+
+    /// Open an existing clone of a repo in the editor
+    OpenLocal { repo: crate::FullName },
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct IssueFilter {
+    #[clap(long, value_enum, default_value = "open")]
+    pub state: IssueState,
+
+    /// Only issues with this label, may be repeated
+    #[clap(long = "label")]
+    pub labels: Vec<String>,
+
+    /// A login, * for any assignee or none for unassigned issues
+    #[clap(long)]
+    pub assignee: Option<String>,
+
+    /// A milestone number, * for any milestone or none
+    #[clap(long)]
+    pub milestone: Option<String>,
+
+    #[clap(long, default_value = "1")]
+    pub page: u32,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum IssueState {
+    Open,
+    Closed,
+    All,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum IssueMethod {
+    /// Create an issue from [owner/name] "title +label @assignee" [body]
+    Create {
+        #[clap(required = true, num_args = 1..=3)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum NotificationMethod {
+    Read { thread: u64 },
+    Done { thread: u64 },
+    Unsubscribe { thread: u64 },
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum GistMethod {
+    /// Create a gist from files, or from the clipboard when none are given
+    Create {
+        /// make the gist public instead of secret
+        #[clap(long)]
+        public: bool,
+
+        #[clap(long, short)]
+        description: Option<String>,
+
+        /// file name for clipboard contents
+        #[clap(long, default_value = "snippet.txt")]
+        filename: String,
+
+        files: Vec<std::path::PathBuf>,
+    },
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ProjectMethod {
+    /// Set the Status of an item, all arguments are graphql node ids
+    Move {
+        project: String,
+        item: String,
+        field: String,
+        option: String,
+    },
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum PullMethod {
+    /// Approve, optionally with a comment
+    Approve {
+        #[clap(flatten)]
+        pull: PullArgs,
+        body: Option<String>,
+    },
+
+    /// Request changes, the comment is required
+    RequestChanges {
+        #[clap(flatten)]
+        pull: PullArgs,
+        body: Option<String>,
+    },
+
+    /// Merge with the configured merge method
+    Merge {
+        #[clap(flatten)]
+        pull: PullArgs,
+    },
+
+    /// Merge with the configured merge method once checks pass
+    AutoMerge {
+        #[clap(flatten)]
+        pull: PullArgs,
+    },
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct PullArgs {
+    pub repo: crate::FullName,
+    pub number: u64,
+
+    /// do it, instead of printing an item asking for confirmation
+    #[clap(long)]
+    pub confirm: bool,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum RunMethod {
+    /// Re-run the failed jobs of a run
+    Rerun { repo: crate::FullName, run: u64 },
+
+    /// Cancel a run that is in progress
+    Cancel { repo: crate::FullName, run: u64 },
+
+    /// Open the logs of the failed job, or the first job
+    Logs { repo: crate::FullName, run: u64 },
 }
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum SearchQuery {
-    Reviews,
-    Pulls,
-    Custom { query: String },
+    /// List the saved searches
+    List,
+
+    /// Run a saved search from the config
+    Saved {
+        name: String,
+
+        #[clap(flatten)]
+        options: SearchOptions,
+    },
+
+    /// Run a search query
+    Custom {
+        query: String,
+
+        #[clap(flatten)]
+        options: SearchOptions,
+    },
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct SearchOptions {
+    /// Text typed in alfred, for {input}
+    pub input: Option<String>,
+
+    /// The repo for {repo}
+    #[clap(long)]
+    pub repo: Option<crate::FullName>,
+
+    #[clap(long, default_value = "1")]
+    pub page: u32,
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use eyre::Result;
+use futures::Future;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::fs;
+
+/// When a cache file was written.
+pub type Since = DateTime<Utc>;
+
+/// Whatever is in `file` if it was written less than `expires` ago, otherwise
+/// the result of `fetch`, which is written to `file` for next time.
+///
+/// A file that doesn't deserialize, e.g. from before a change to `T`, is
+/// treated as expired.
+pub async fn cached<P, F, Fut, T>(file: P, expires: Duration, fetch: F) -> Result<T>
+where
+    P: AsRef<Path>,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    T: Serialize + DeserializeOwned,
+{
+    match read(file.as_ref()).await {
+        Ok((since, value)) if Utc::now() - since < expires => Ok(value),
+        _ => {
+            let value = fetch().await?;
+            write(file, &value).await?;
+
+            Ok(value)
+        }
+    }
+}
+
+/// What is in `file` and when it was written, regardless of its age.
+pub async fn read<T: DeserializeOwned>(file: &Path) -> Result<(Since, T)> {
+    let value = fs::read_to_string(file).await?;
+    let value = serde_json::from_str(&value)?;
+    let since = fs::metadata(file).await?.modified()?;
+
+    Ok((DateTime::from(since), value))
+}
+
+pub async fn write<P: AsRef<Path>, T: Serialize>(file: P, value: &T) -> Result<()> {
+    if let Some(parent) = file.as_ref().parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(file.as_ref(), serde_json::to_string(value)?).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cached() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("nested/numbers.json");
+        let hour = Duration::hours(1);
+
+        let numbers = cached(&file, hour, || async { Ok(vec![1, 2]) })
+            .await
+            .unwrap();
+        assert_eq!(numbers, vec![1, 2]);
+
+        // fresh, so fetch isn't called
+        let numbers: Vec<u32> = cached(&file, hour, || async { unreachable!() })
+            .await
+            .unwrap();
+        assert_eq!(numbers, vec![1, 2]);
+
+        // expired
+        let numbers = cached(&file, Duration::zero(), || async { Ok(vec![3]) })
+            .await
+            .unwrap();
+        assert_eq!(numbers, vec![3]);
+
+        // a different type doesn't deserialize, so it's fetched again
+        let strings = cached(&file, hour, || async { Ok(vec!["a".to_string()]) })
+            .await
+            .unwrap();
+        assert_eq!(strings, vec!["a".to_string()]);
+
+        // and errors aren't cached
+        let err = cached::<_, _, _, Vec<u32>>(&file, Duration::zero(), || async {
+            Err(eyre::eyre!("offline"))
+        })
+        .await;
+        assert!(err.is_err());
+        assert!(file.exists());
+    }
+}
@@ -3,7 +3,11 @@ use std::path::PathBuf;
 use eyre::{ContextCompat, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::actions::{copy::CopyConfig, search_issues::SearchIssuesConfig};
+use crate::actions::{
+    alerts::AlertsConfig, clone::CloneConfig, copy::CopyConfig, issues::IssuesConfig,
+    pulls::PullsConfig, repos::ReposConfig, search_code::SearchCodeConfig,
+    search_issues::SearchIssuesConfig,
+};
 
 // TODO: later this could function as app config for the workflow
 // I like to customize this per-machine, so it's stored in the workflow data dir
@@ -13,6 +17,24 @@ pub struct Config {
 
     #[serde(default)]
     pub copy: CopyConfig,
+
+    #[serde(default)]
+    pub clone: CloneConfig,
+
+    #[serde(default)]
+    pub issues: IssuesConfig,
+
+    #[serde(default)]
+    pub search_code: SearchCodeConfig,
+
+    #[serde(default)]
+    pub pulls: PullsConfig,
+
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+
+    #[serde(default)]
+    pub repos: ReposConfig,
 }
 
 impl Config {
@@ -1,17 +1,58 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use eyre::Result;
-use tokio::fs;
+use once_cell::sync::Lazy;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use tokio::{fs, io::AsyncWriteExt};
 
 use crate::alfred::{Items, ALFRED_WORKFLOW_CACHE};
 
+/// The client for everything that doesn't go through octocrab.
+///
+/// github rejects api requests without a user agent.
+pub static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()
+        .expect("failed to build http client")
+});
+
+/// What needs escaping in a branch or tag name to use it in a url path.
+///
+/// Slashes are kept, github takes `compare/main...feature/x` as it is.
+const REF: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'+')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b']')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// A branch or tag name escaped for a url path, e.g. in a compare url.
+pub fn encode_ref(name: &str) -> String {
+    utf8_percent_encode(name, REF).to_string()
+}
+
 pub async fn fetch_avatars(items: &Items) -> Result<()> {
-    let http_client = reqwest::Client::new();
+    let http_client = &*HTTP_CLIENT;
     let cache_dir = ALFRED_WORKFLOW_CACHE.as_ref()?;
 
     let fetches = items
         .owners()
-        .map(|ref owner| fetch_github_user_avatar(&http_client, cache_dir, owner.clone()));
+        .map(|ref owner| fetch_github_user_avatar(http_client, cache_dir, owner.clone()));
     futures::future::join_all(fetches).await;
 
     Ok(())
@@ -39,3 +80,123 @@ where
 
     Ok(())
 }
+
+/// Stream the response to `request` into `path`, calling `progress` with the
+/// bytes written so far and the content length, if known.
+///
+/// The body goes to a .part file first so an interrupted download never
+/// looks finished. An existing file is left alone and the download is
+/// renamed like browsers do, see [unused_path], so this returns where it went.
+pub async fn download<P, F>(
+    request: reqwest::RequestBuilder,
+    path: P,
+    mut progress: F,
+) -> Result<PathBuf>
+where
+    P: AsRef<Path>,
+    F: FnMut(u64, Option<u64>),
+{
+    let path = path.as_ref();
+    let mut res = request.send().await?.error_for_status()?;
+    let total = res.content_length();
+    let part = path.with_extension(match path.extension() {
+        Some(ext) => format!("{}.part", ext.to_string_lossy()),
+        None => "part".to_string(),
+    });
+    let mut file = fs::File::create(&part).await?;
+    let mut written = 0;
+    while let Some(chunk) = res.chunk().await? {
+        file.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+        progress(written, total);
+    }
+    file.flush().await?;
+    let path = unused_path(path);
+    fs::rename(&part, &path).await?;
+
+    Ok(path)
+}
+
+/// `path` if nothing is there yet, otherwise the first of `name (1).ext`,
+/// `name (2).ext`... that is free.
+fn unused_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // before every extension, so asset.tar.gz becomes asset (1).tar.gz,
+    // but not the dot of a hidden file
+    let (stem, ext) = match name.char_indices().skip(1).find(|(_, c)| *c == '.') {
+        Some((i, _)) => name.split_at(i),
+        None => (name.as_str(), ""),
+    };
+    (1..)
+        .map(|n| path.with_file_name(format!("{stem} ({n}){ext}")))
+        .find(|path| !path.exists())
+        .expect("some name is free")
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::AsyncReadExt, net::TcpListener};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_download() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let body = "x".repeat(100_000);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await.unwrap();
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("asset.tar.gz");
+        let mut updates = vec![];
+        let request = HTTP_CLIENT.get(format!("http://{addr}/asset.tar.gz"));
+        let downloaded = download(request, &path, |written, total| {
+            updates.push((written, total))
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(downloaded, path);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), body);
+        assert!(!tmp.path().join("asset.tar.gz.part").exists());
+        assert_eq!(updates.last(), Some(&(100_000, Some(100_000))));
+    }
+
+    #[test]
+    fn test_encode_ref() {
+        assert_eq!(encode_ref("feature/x"), "feature/x");
+        assert_eq!(encode_ref("fix#12"), "fix%2312");
+        assert_eq!(encode_ref("c++ é"), "c%2B%2B%20%C3%A9");
+    }
+
+    #[test]
+    fn test_unused_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("asset.tar.gz");
+        assert_eq!(unused_path(&path), path);
+
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(unused_path(&path), tmp.path().join("asset (1).tar.gz"));
+        std::fs::write(tmp.path().join("asset (1).tar.gz"), "").unwrap();
+        assert_eq!(unused_path(&path), tmp.path().join("asset (2).tar.gz"));
+
+        let hidden = tmp.path().join(".env");
+        std::fs::write(&hidden, "").unwrap();
+        assert_eq!(unused_path(&hidden), tmp.path().join(".env (1)"));
+    }
+}
@@ -0,0 +1,165 @@
+pub mod branches;
+pub mod discussions;
+pub mod enable_auto_merge;
+pub mod move_project_item;
+pub mod project_items;
+pub mod repo_hub;
+pub mod search_users;
+pub mod viewer_projects;
+pub mod viewer_repos;
+
+use eyre::Result;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use serde_json::json;
+
+use crate::OCTOCRAB;
+
+/// A query in one of the .graphql files next to this module, along with the
+/// types of its variables and response data.
+pub trait Query {
+    /// the operation name, used when reporting errors and cost
+    const NAME: &'static str;
+    const TEXT: &'static str;
+
+    /// whether errors alongside data are only reported rather than failing,
+    /// e.g. for orgs that hide their projects behind SAML
+    const PARTIAL: bool = false;
+
+    type Variables: Serialize;
+    type Data: DeserializeOwned;
+}
+
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    data: Option<Data<T>>,
+    #[serde(default)]
+    errors: Vec<Error>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Data<T> {
+    /// every query asks for the rateLimit so its cost can be reported
+    #[serde(rename = "rateLimit")]
+    rate_limit: Option<RateLimit>,
+    #[serde(flatten)]
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct Error {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimit {
+    cost: u32,
+    remaining: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "T: Deserialize<'de>")]
+pub struct Connection<T> {
+    #[serde(deserialize_with = "nodes")]
+    pub nodes: Vec<T>,
+    /// only there when the query asks for it
+    #[serde(default)]
+    pub page_info: PageInfo,
+}
+
+/// Nodes that the viewer can't see are null, leave them out.
+fn nodes<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let nodes = Vec::<Option<T>>::deserialize(deserializer)?;
+
+    Ok(nodes.into_iter().flatten().collect())
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+impl<T> Connection<T> {
+    /// The cursor to pass as `after` for the next page, None on the last page.
+    pub fn next_cursor(&self) -> Option<String> {
+        match self.page_info.has_next_page {
+            true => self.page_info.end_cursor.clone(),
+            false => None,
+        }
+    }
+}
+
+/// Run a query and report its cost on stderr, which ends up in alfred's debugger.
+///
+/// Errors in the response are an error even when there is partial data,
+/// unless the query is `PARTIAL`.
+pub async fn query<Q: Query>(variables: &Q::Variables) -> Result<Q::Data> {
+    let response: Response<Q::Data> = OCTOCRAB
+        .graphql(&json!({ "query": Q::TEXT, "variables": variables }))
+        .await?;
+    if !response.errors.is_empty() {
+        let messages = response
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !Q::PARTIAL || response.data.is_none() {
+            return Err(eyre::eyre!("{name} failed: {messages}", name = Q::NAME));
+        }
+        eprintln!("{name} partially failed: {messages}", name = Q::NAME);
+    }
+    let data = response
+        .data
+        .ok_or_else(|| eyre::eyre!("{} returned no data", Q::NAME))?;
+    if let Some(RateLimit { cost, remaining }) = data.rate_limit {
+        eprintln!("{name} cost {cost}, {remaining} remaining", name = Q::NAME);
+    }
+
+    Ok(data.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response() {
+        let response: Response<serde_json::Value> = serde_json::from_str(
+            r#"{
+                "data": {
+                    "viewer": { "login": "dylanwh" },
+                    "rateLimit": { "cost": 1, "remaining": 4999, "resetAt": "2023-10-01T00:00:00Z" }
+                }
+            }"#,
+        )
+        .unwrap();
+        let data = response.data.unwrap();
+        assert_eq!(data.rate_limit.unwrap().remaining, 4999);
+        assert_eq!(data.data["viewer"]["login"], "dylanwh");
+        assert!(response.errors.is_empty());
+
+        let connection: Connection<u32> = serde_json::from_str(
+            r#"{ "nodes": [1, 2], "pageInfo": { "hasNextPage": true, "endCursor": "Y3Vy" } }"#,
+        )
+        .unwrap();
+        assert_eq!(connection.next_cursor().as_deref(), Some("Y3Vy"));
+
+        let connection: Connection<u32> = serde_json::from_str(
+            r#"{ "nodes": [], "pageInfo": { "hasNextPage": false, "endCursor": null } }"#,
+        )
+        .unwrap();
+        assert_eq!(connection.next_cursor(), None);
+
+        let connection: Connection<u32> =
+            serde_json::from_str(r#"{ "nodes": [1, null, 3] }"#).unwrap();
+        assert_eq!(connection.nodes, vec![1, 3]);
+        assert_eq!(connection.next_cursor(), None);
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Connection, Query};
+
+pub struct Branches;
+
+impl Query for Branches {
+    const NAME: &'static str = "Branches";
+    const TEXT: &'static str = include_str!("branches.graphql");
+
+    type Variables = Variables;
+    type Data = Data;
+}
+
+#[derive(Debug, Serialize)]
+pub struct Variables {
+    pub owner: String,
+    pub name: String,
+    /// the branch everything is compared with, normally the default branch
+    pub base: String,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data {
+    pub repository: Option<Repository>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Repository {
+    pub url: String,
+    pub refs: Connection<Ref>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ref {
+    pub name: String,
+    /// the protection rule that applies to the branch, if any
+    pub ref_update_rule: Option<RefUpdateRule>,
+    pub compare: Option<Comparison>,
+    pub associated_pull_requests: Connection<PullRequest>,
+    pub target: Option<Target>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefUpdateRule {
+    pub pattern: String,
+}
+
+/// From the branch to the base, see branches.graphql
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comparison {
+    pub ahead_by: u64,
+    pub behind_by: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Target {
+    /// None when the branch points at something other than a commit
+    pub committed_date: Option<DateTime<Utc>>,
+}
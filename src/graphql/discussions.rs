@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Connection, Query};
+
+pub struct Discussions;
+
+impl Query for Discussions {
+    const NAME: &'static str = "Discussions";
+    const TEXT: &'static str = include_str!("discussions.graphql");
+
+    type Variables = Variables;
+    type Data = Data;
+}
+
+#[derive(Debug, Serialize)]
+pub struct Variables {
+    pub owner: String,
+    pub name: String,
+    /// a search query, only used when `search` is set
+    pub query: String,
+    pub search: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data {
+    pub repository: Option<Repository>,
+    pub search: Option<Connection<Discussion>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Repository {
+    pub discussions: Connection<Discussion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Discussion {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// None when the category doesn't take answers
+    pub is_answered: Option<bool>,
+    /// None for deleted accounts
+    pub author: Option<Author>,
+    pub category: Category,
+    pub comments: Comments,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Author {
+    pub login: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Category {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comments {
+    pub total_count: u64,
+}
@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+use super::Query;
+
+pub struct EnableAutoMerge;
+
+impl Query for EnableAutoMerge {
+    const NAME: &'static str = "EnableAutoMerge";
+    const TEXT: &'static str = include_str!("enable_auto_merge.graphql");
+
+    type Variables = Variables;
+    /// nothing in the response is needed
+    type Data = serde_json::Value;
+}
+
+#[derive(Debug, Serialize)]
+pub struct Variables {
+    /// the node id of the pull request
+    pub pull: String,
+    /// MERGE, SQUASH or REBASE
+    pub method: String,
+}
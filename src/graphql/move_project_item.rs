@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+use super::Query;
+
+pub struct MoveProjectItem;
+
+impl Query for MoveProjectItem {
+    const NAME: &'static str = "MoveProjectItem";
+    const TEXT: &'static str = include_str!("move_project_item.graphql");
+
+    type Variables = Variables;
+    /// nothing in the response is needed
+    type Data = serde_json::Value;
+}
+
+#[derive(Debug, Serialize)]
+pub struct Variables {
+    pub project: String,
+    pub item: String,
+    pub field: String,
+    pub option: String,
+}
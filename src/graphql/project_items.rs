@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Connection, Query};
+
+pub struct ProjectItems;
+
+impl Query for ProjectItems {
+    const NAME: &'static str = "ProjectItems";
+    const TEXT: &'static str = include_str!("project_items.graphql");
+
+    type Variables = Variables;
+    type Data = Data;
+}
+
+#[derive(Debug, Serialize)]
+pub struct Variables {
+    pub project: String,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data {
+    pub node: Option<Project>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Project {
+    pub title: String,
+    pub url: String,
+    pub field: Option<StatusField>,
+    pub items: Connection<ProjectItem>,
+}
+
+/// The Status field, id is None when Status isn't a single select.
+#[derive(Debug, Deserialize)]
+pub struct StatusField {
+    pub id: Option<String>,
+    #[serde(default)]
+    pub options: Vec<StatusOption>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatusOption {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectItem {
+    pub id: String,
+    /// ISSUE, PULL_REQUEST, DRAFT_ISSUE or REDACTED
+    pub r#type: String,
+    pub status: Option<StatusValue>,
+    pub field_values: Connection<FieldValue>,
+    /// None when redacted
+    pub content: Option<Content>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusValue {
+    pub option_id: Option<String>,
+}
+
+/// Only iteration values have a title, the other field values are empty.
+#[derive(Debug, Deserialize)]
+pub struct FieldValue {
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Content {
+    pub title: String,
+    pub number: Option<u64>,
+    pub url: Option<String>,
+    pub repository: Option<Repository>,
+    pub assignees: Connection<Login>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Repository {
+    pub name_with_owner: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Login {
+    pub login: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_item() {
+        let item: ProjectItem = serde_json::from_str(
+            r#"{
+                "id": "PVTI_1",
+                "type": "DRAFT_ISSUE",
+                "status": null,
+                "fieldValues": { "nodes": [{}, { "title": "Iteration 3" }, {}] },
+                "content": { "title": "Write docs", "assignees": { "nodes": [{ "login": "dylanwh" }] } }
+            }"#,
+        )
+        .unwrap();
+        assert!(item.status.is_none());
+        assert_eq!(
+            item.field_values.nodes[1].title.as_deref(),
+            Some("Iteration 3")
+        );
+        let content = item.content.unwrap();
+        assert_eq!(content.number, None);
+        assert_eq!(content.assignees.nodes[0].login, "dylanwh");
+    }
+}
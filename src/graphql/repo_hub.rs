@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Connection, Query};
+
+pub struct RepoHub;
+
+impl Query for RepoHub {
+    const NAME: &'static str = "RepoHub";
+    const TEXT: &'static str = include_str!("repo_hub.graphql");
+
+    type Variables = Variables;
+    type Data = Data;
+}
+
+#[derive(Debug, Serialize)]
+pub struct Variables {
+    pub owner: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data {
+    pub repository: Option<Repository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Repository {
+    pub name_with_owner: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub stargazer_count: u64,
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub primary_language: Option<Language>,
+    pub repository_topics: Connection<RepositoryTopic>,
+    pub branches: Count,
+    pub pull_requests: Count,
+    pub issues: Count,
+    pub latest_release: Option<Release>,
+    /// None for empty repos
+    pub default_branch_ref: Option<Ref>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Language {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RepositoryTopic {
+    pub topic: Topic,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Topic {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Count {
+    pub total_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Release {
+    pub name: Option<String>,
+    pub tag_name: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Ref {
+    pub name: String,
+    pub target: Option<Target>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Target {
+    /// None when there are no checks, or the target isn't a commit
+    pub status_check_rollup: Option<StatusCheckRollup>,
+}
+
+/// SUCCESS, FAILURE, PENDING, ERROR or EXPECTED
+#[derive(Debug, Deserialize)]
+pub struct StatusCheckRollup {
+    pub state: String,
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Connection, Query};
+
+pub struct SearchUsers;
+
+impl Query for SearchUsers {
+    const NAME: &'static str = "SearchUsers";
+    const TEXT: &'static str = include_str!("search_users.graphql");
+
+    type Variables = Variables;
+    type Data = Data;
+}
+
+#[derive(Debug, Serialize)]
+pub struct Variables {
+    pub query: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data {
+    pub search: Connection<Account>,
+}
+
+/// A user or an organization, they share most fields.
+#[derive(Debug, Deserialize)]
+pub struct Account {
+    #[serde(rename = "__typename")]
+    pub typename: String,
+    pub login: String,
+    pub name: Option<String>,
+    pub url: String,
+    /// users only
+    #[serde(default)]
+    pub company: Option<String>,
+    /// organizations only
+    #[serde(default)]
+    pub description: Option<String>,
+}
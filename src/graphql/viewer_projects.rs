@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{Connection, Query};
+
+pub struct ViewerProjects;
+
+impl Query for ViewerProjects {
+    const NAME: &'static str = "ViewerProjects";
+    const TEXT: &'static str = include_str!("viewer_projects.graphql");
+    const PARTIAL: bool = true;
+
+    type Variables = ();
+    type Data = Data;
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data {
+    pub viewer: Viewer,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Viewer {
+    pub login: String,
+    pub projects_v2: Connection<Project>,
+    pub organizations: Connection<Organization>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub login: String,
+    /// None when the org requires SAML sign on
+    pub projects_v2: Option<Connection<Project>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub id: String,
+    pub number: u64,
+    pub title: String,
+    pub short_description: Option<String>,
+    pub url: String,
+    pub closed: bool,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Connection, Query};
+
+pub struct ViewerRepos;
+
+impl Query for ViewerRepos {
+    const NAME: &'static str = "ViewerRepos";
+    const TEXT: &'static str = include_str!("viewer_repos.graphql");
+
+    type Variables = Variables;
+    type Data = Data;
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Variables {
+    pub repos_after: Option<String>,
+    pub stars_after: Option<String>,
+    pub with_repos: bool,
+    pub with_stars: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data {
+    pub viewer: Viewer,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Viewer {
+    pub repositories: Option<Connection<Repository>>,
+    pub starred_repositories: Option<Connection<Repository>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Repository {
+    pub name_with_owner: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub is_archived: bool,
+    pub updated_at: DateTime<Utc>,
+}
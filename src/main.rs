@@ -1,22 +1,23 @@
 mod actions;
 mod alfred;
 mod args;
+mod cache;
 mod config;
 mod github_util;
+mod graphql;
 mod hub_compat;
+mod reference;
+mod template;
 
-use std::{
-    fmt::{Display, Formatter},
-    str::FromStr,
-    sync::Arc,
-};
+use std::sync::Arc;
 
-use args::{Action, Args};
+use args::{Action, Args, GistMethod, IssueMethod};
 use config::Config;
 use eyre::Result;
 use hub_compat::HubConfig;
 use octocrab::Octocrab;
 use once_cell::sync::Lazy;
+pub use reference::FullName;
 
 /// A global instance of Octocrab.
 ///
@@ -40,42 +41,92 @@ async fn main() -> Result<()> {
         Action::Install => actions::install::run()?,
         Action::Refresh => actions::refresh::run().await?,
         Action::Config { method } => actions::config::run(method).await?,
-        Action::Repos { no_cache } => actions::repos::run(no_cache).await?,
+        Action::Repos { no_cache } => {
+            let config = Config::load().await?;
+            actions::repos::run(&config.repos, no_cache).await?
+        }
         Action::Pulls { repo } => actions::pulls::run(repo).await?,
+        Action::Issues { repo, filter } => actions::issues::run(repo, filter).await?,
+        Action::Issue { method } => {
+            let config = Config::load().await?;
+            match method {
+                IssueMethod::Create { args } => {
+                    actions::issues::create(&config.issues, args).await?
+                }
+            }
+        }
+        Action::Notifications => actions::notifications::run().await?,
+        Action::Notification { method } => actions::notifications::update(method).await?,
+        Action::Feed { no_cache } => actions::feed::run(no_cache).await?,
+        Action::Alerts => {
+            let config = Config::load().await?;
+            actions::alerts::run(&config.alerts).await?
+        }
+        Action::Discussions { repo, query } => actions::discussions::run(repo, query).await?,
+        Action::Gists { query } => actions::gists::run(query).await?,
+        Action::Gist {
+            method:
+                GistMethod::Create {
+                    public,
+                    description,
+                    filename,
+                    files,
+                },
+        } => actions::gists::create(public, description, filename, files).await?,
+        Action::Pull { method } => {
+            let config = Config::load().await?;
+            actions::pulls::update(&config.pulls, method).await?
+        }
+        Action::Projects => actions::projects::run().await?,
+        Action::ProjectItems { project } => actions::projects::items(project).await?,
+        Action::Project { method } => actions::projects::update(method).await?,
+        Action::Repo { repo } => actions::repo::run(repo).await?,
+        Action::Branches { repo } => actions::branches::run(repo).await?,
+        Action::Commits { repo } => actions::commits::run(repo).await?,
+        Action::Compare { repo } => {
+            let config = Config::load().await?;
+            actions::compare::run(&config.clone, repo).await?
+        }
+        Action::CreatePull { repo } => {
+            let config = Config::load().await?;
+            actions::compare::create_pull(&config.clone, repo).await?
+        }
+        Action::Releases { repo } => actions::releases::run(repo).await?,
+        Action::Assets { repo, release } => actions::releases::assets(repo, release).await?,
+        Action::Download { repo, asset } => actions::releases::download(repo, asset).await?,
+        Action::Runs { repo } => actions::runs::run(repo).await?,
+        Action::Run { method } => actions::runs::update(method).await?,
         Action::SearchIssues { query } => {
             let config = Config::load().await?;
             actions::search_issues::run(&config.search_issues, query).await?
         }
+        Action::SearchRepos { query } => {
+            let config = Config::load().await?;
+            actions::search_repos::run(&config.repos, query).await?
+        }
+        Action::SearchCode { query, repo } => {
+            let config = Config::load().await?;
+            actions::search_code::run(&config.search_code, query, repo).await?
+        }
+        Action::Users { query } => actions::users::run(query).await?,
+        Action::Star { repo } => actions::search_repos::toggle_star(repo).await?,
         Action::Copy => {
             let config = Config::load().await?;
             actions::copy::run(&config.copy).await?
         }
+        Action::OpenReference { input, repo } => {
+            let config = Config::load().await?;
+            actions::open_reference::run(&config.repos, input, repo).await?
+        }
+        Action::Clone { repo, url } => {
+            let config = Config::load().await?;
+            actions::clone::run(&config.clone, repo, url).await?
+        }
+        Action::OpenLocal { repo } => {
+            let config = Config::load().await?;
+            actions::clone::open_local(&config.clone, repo).await?
+        }
     }
 
     Ok(())
 }
-
-#[derive(Clone, Debug)]
-pub struct FullName {
-    pub owner: String,
-    pub name: String,
-}
-
-impl Display for FullName {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}/{}", self.owner, self.name)
-    }
-}
-
-impl FromStr for FullName {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.split_once('/') {
-            Some((owner, name)) => Ok(Self {
-                owner: owner.to_string(),
-                name: name.to_string(),
-            }),
-            None => Err("not a github repository full-name".to_string()),
-        }
-    }
-}
@@ -0,0 +1,493 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+static SCHEMES: &[&str] = &["https://", "http://", "ssh://", "git+ssh://", "git://"];
+
+/// The owner/name of a github repository.
+///
+/// Parsing accepts anything a [`Reference`] on github.com does and keeps only the repository part,
+/// enterprise hosts are refused since the api can't reach them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FullName {
+    pub owner: String,
+    pub name: String,
+}
+
+impl FullName {
+    pub fn new(owner: &str, name: &str) -> Result<Self, String> {
+        let name = name.strip_suffix(".git").unwrap_or(name);
+        validate_owner(owner)?;
+        validate_name(name)?;
+
+        Ok(Self {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
+
+impl Display for FullName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+impl FromStr for FullName {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let reference = Reference::from_str(s)?;
+        if !reference.is_github() {
+            return Err(format!("{} is not a github.com repository", reference.host));
+        }
+
+        Ok(reference.full_name)
+    }
+}
+
+impl Serialize for FullName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for FullName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A repository with an optional branch, written `owner/name@branch`.
+///
+/// Anything that isn't that form is parsed as a [`FullName`] without a branch,
+/// so urls work too.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepoBranch {
+    pub full_name: FullName,
+    pub branch: Option<String>,
+}
+
+impl FromStr for RepoBranch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // the owner of an scp-like url (git@github.com:...) isn't a full name,
+        // so those fall through to FullName
+        if let Some((repo, branch)) = s.split_once('@') {
+            if let (Ok(full_name), false) = (repo.parse::<FullName>(), branch.is_empty()) {
+                return Ok(Self {
+                    full_name,
+                    branch: Some(branch.to_string()),
+                });
+            }
+        }
+
+        Ok(Self {
+            full_name: s.parse()?,
+            branch: None,
+        })
+    }
+}
+
+/// Something on github that text pasted from a browser or chat points at.
+///
+/// This understands `owner/name`, `owner/name#123`, https/ssh/git urls
+/// (with or without `.git`, on github.com or an enterprise host)
+/// `/pull/N`, `/issues/N` or `/commit/SHA` paths and `owner/name@SHA`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reference {
+    pub host: String,
+    pub full_name: FullName,
+    pub target: Option<Target>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// `owner/name#123` could be either an issue or a pull request
+    Number(u64),
+    Issue(u64),
+    Pull(u64),
+    Commit(String),
+}
+
+/// Parses the bare forms that need a repository from elsewhere: `#123` or a commit sha.
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.strip_prefix('#') {
+            Some(number) => Ok(Self::Number(parse_number(number)?)),
+            None => Ok(Self::Commit(parse_sha(s)?)),
+        }
+    }
+}
+
+impl FromStr for Reference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(rest) = SCHEMES.iter().find_map(|scheme| s.strip_prefix(scheme)) {
+            let (authority, path) = rest
+                .split_once('/')
+                .ok_or_else(|| format!("missing path in url {s}"))?;
+            return parse_path(host(authority), path);
+        }
+        // scp-like ssh urls, e.g. git@github.com:owner/name.git
+        if let Some((authority, path)) = s.split_once(':') {
+            if authority.contains('@') {
+                return parse_path(host(authority), path);
+            }
+        }
+        // a host without a scheme, e.g. github.com/owner/name
+        // owners cannot contain dots, so this isn't ambiguous.
+        if let Some((authority, path)) = s.split_once('/') {
+            if authority.contains('.') {
+                return parse_path(host(authority), path);
+            }
+        }
+
+        let (repo, target) = if let Some((repo, number)) = s.split_once('#') {
+            (repo, Some(Target::Number(parse_number(number)?)))
+        } else if let Some((repo, sha)) = s.split_once('@') {
+            (repo, Some(Target::Commit(parse_sha(sha)?)))
+        } else {
+            (s, None)
+        };
+        let (owner, name) = repo
+            .split_once('/')
+            .ok_or_else(|| "not a github repository full-name".to_string())?;
+
+        Ok(Self {
+            host: "github.com".to_string(),
+            full_name: FullName::new(owner, name)?,
+            target,
+        })
+    }
+}
+
+impl Reference {
+    /// Whether the api behind OCTOCRAB can look this up, enterprise hosts have their own.
+    pub fn is_github(&self) -> bool {
+        matches!(self.host.as_str(), "github.com" | "www.github.com")
+    }
+
+    /// The web page for the reference, on whichever host it came from.
+    pub fn html_url(&self) -> String {
+        let repo_url = format!("https://{}/{}", self.host, self.full_name);
+        match &self.target {
+            None => repo_url,
+            Some(Target::Pull(number)) => format!("{repo_url}/pull/{number}"),
+            // github redirects issues/N to pull/N when it is a pull request
+            Some(Target::Issue(number) | Target::Number(number)) => {
+                format!("{repo_url}/issues/{number}")
+            }
+            Some(Target::Commit(sha)) => format!("{repo_url}/commit/{sha}"),
+        }
+    }
+}
+
+fn host(authority: &str) -> &str {
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    host.split_once(':').map_or(host, |(host, _)| host)
+}
+
+fn parse_path(host: &str, path: &str) -> Result<Reference, String> {
+    if host.is_empty() {
+        return Err("missing host".to_string());
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    let (Some(owner), Some(name)) = (segments.next(), segments.next()) else {
+        return Err(format!("expected owner/name in {path}"));
+    };
+    let target = match (segments.next(), segments.next()) {
+        (Some("pull"), Some(number)) => Some(Target::Pull(parse_number(number)?)),
+        (Some("issues"), Some(number)) => Some(Target::Issue(parse_number(number)?)),
+        (Some("commit"), Some(sha)) => Some(Target::Commit(parse_sha(sha)?)),
+        _ => None,
+    };
+
+    Ok(Reference {
+        host: host.to_lowercase(),
+        full_name: FullName::new(owner, name)?,
+        target,
+    })
+}
+
+fn parse_number(s: &str) -> Result<u64, String> {
+    match s.parse() {
+        Ok(0) | Err(_) => Err(format!("{s} is not an issue or pull request number")),
+        Ok(n) => Ok(n),
+    }
+}
+
+fn parse_sha(s: &str) -> Result<String, String> {
+    if (7..=40).contains(&s.len()) && s.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(s.to_lowercase())
+    } else {
+        Err(format!("{s} is not a commit sha"))
+    }
+}
+
+/// Users and organizations: up to 39 alphanumerics or hyphens, not starting or
+/// ending with a hyphen. Enterprise managed users also have an underscore, e.g. jdoe_acme.
+fn validate_owner(owner: &str) -> Result<(), String> {
+    let valid = (1..=39).contains(&owner.len())
+        && owner
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        && !owner.starts_with('-')
+        && !owner.ends_with('-');
+    if !valid {
+        return Err(format!("{owner:?} is not a valid github owner"));
+    }
+
+    Ok(())
+}
+
+/// Repositories: up to 100 alphanumerics, hyphens, underscores or dots,
+/// excluding "." and "..".
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = (1..=100).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && name != "."
+        && name != "..";
+    if !valid {
+        return Err(format!("{name:?} is not a valid github repository name"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn reference(host: &str, owner: &str, name: &str, target: Option<Target>) -> Reference {
+        Reference {
+            host: host.to_string(),
+            full_name: FullName::new(owner, name).unwrap(),
+            target,
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        let examples = [
+            ("dylanwh/alfred-workflow-github", None),
+            (
+                "dylanwh/alfred-workflow-github#12",
+                Some(Target::Number(12)),
+            ),
+            ("https://github.com/dylanwh/alfred-workflow-github", None),
+            ("https://github.com/dylanwh/alfred-workflow-github/", None),
+            (
+                "https://github.com/dylanwh/alfred-workflow-github.git",
+                None,
+            ),
+            (
+                "https://github.com/dylanwh/alfred-workflow-github/pull/12/files",
+                Some(Target::Pull(12)),
+            ),
+            (
+                "https://github.com/dylanwh/alfred-workflow-github/issues/12#issuecomment-1",
+                Some(Target::Issue(12)),
+            ),
+            ("github.com/dylanwh/alfred-workflow-github/tree/main", None),
+            ("git@github.com:dylanwh/alfred-workflow-github.git", None),
+            (
+                "ssh://git@github.com:22/dylanwh/alfred-workflow-github",
+                None,
+            ),
+            ("git://github.com/dylanwh/alfred-workflow-github.git", None),
+            (
+                "dylanwh/alfred-workflow-github@9835EA6",
+                Some(Target::Commit("9835ea6".to_string())),
+            ),
+            (
+                "https://github.com/dylanwh/alfred-workflow-github/commit/9835ea6",
+                Some(Target::Commit("9835ea6".to_string())),
+            ),
+        ];
+        for (input, target) in examples {
+            assert_eq!(
+                input.parse::<Reference>(),
+                Ok(reference(
+                    "github.com",
+                    "dylanwh",
+                    "alfred-workflow-github",
+                    target
+                )),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_enterprise() {
+        assert_eq!(
+            "https://GHE.example.com/org/repo/pull/3".parse::<Reference>(),
+            Ok(reference(
+                "ghe.example.com",
+                "org",
+                "repo",
+                Some(Target::Pull(3))
+            ))
+        );
+    }
+
+    #[test]
+    fn test_html_url() {
+        let reference: Reference = "git@ghe.example.com:org/repo.git".parse().unwrap();
+        assert!(!reference.is_github());
+        assert_eq!(reference.html_url(), "https://ghe.example.com/org/repo");
+
+        let reference: Reference = "org/repo#3".parse().unwrap();
+        assert!(reference.is_github());
+        assert_eq!(reference.html_url(), "https://github.com/org/repo/issues/3");
+    }
+
+    #[test]
+    fn test_managed_user() {
+        assert_eq!(
+            "jdoe_acme/dotfiles".parse(),
+            FullName::new("jdoe_acme", "dotfiles")
+        );
+    }
+
+    #[test]
+    fn test_full_name_host() {
+        assert_eq!(
+            "https://www.github.com/org/repo".parse(),
+            FullName::new("org", "repo")
+        );
+        assert!("https://ghe.example.com/org/repo"
+            .parse::<FullName>()
+            .is_err());
+        assert!("git@ghe.example.com:org/repo.git"
+            .parse::<FullName>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_target() {
+        assert_eq!("#12".parse(), Ok(Target::Number(12)));
+        assert_eq!("9835ea6".parse(), Ok(Target::Commit("9835ea6".to_string())));
+        assert!("#".parse::<Target>().is_err());
+        assert!("9835".parse::<Target>().is_err());
+        assert!("not-a-sha".parse::<Target>().is_err());
+    }
+
+    #[test]
+    fn test_repo_branch() {
+        let full_name = FullName::new("dylanwh", "alfred-workflow-github").unwrap();
+        let examples = [
+            ("dylanwh/alfred-workflow-github", None),
+            ("dylanwh/alfred-workflow-github@main", Some("main")),
+            (
+                "dylanwh/alfred-workflow-github@feature/x",
+                Some("feature/x"),
+            ),
+            ("git@github.com:dylanwh/alfred-workflow-github.git", None),
+            ("https://github.com/dylanwh/alfred-workflow-github", None),
+        ];
+        for (input, branch) in examples {
+            assert_eq!(
+                input.parse::<RepoBranch>(),
+                Ok(RepoBranch {
+                    full_name: full_name.clone(),
+                    branch: branch.map(str::to_string),
+                }),
+                "{input}"
+            );
+        }
+        assert!("dylanwh@main".parse::<RepoBranch>().is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        for input in [
+            "",
+            "a",
+            "a/b/c",
+            "a/b#",
+            "a/b#0",
+            "a/b#x",
+            "a/b@main",
+            "-a/b",
+            "a-/b",
+            "a/..",
+            "a/b c",
+            "https://github.com/a",
+            "https://github.com/a/b/pull/x",
+        ] {
+            assert!(input.parse::<Reference>().is_err(), "{input}");
+        }
+    }
+
+    fn owner() -> impl Strategy<Value = String> {
+        "[a-zA-Z0-9]([a-zA-Z0-9-]{0,37}[a-zA-Z0-9])?"
+    }
+
+    fn name() -> impl Strategy<Value = String> {
+        "[a-zA-Z0-9_.-]{1,100}".prop_filter("reserved", |n| {
+            n != "." && n != ".." && !n.ends_with(".git")
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_full_name_round_trip(owner in owner(), name in name()) {
+            let full_name = FullName::new(&owner, &name).unwrap();
+            prop_assert_eq!(full_name.to_string().parse::<FullName>(), Ok(full_name));
+        }
+
+        #[test]
+        fn prop_forms(owner in owner(), name in name(), number in 1..u64::MAX) {
+            let forms = [
+                (format!("{owner}/{name}#{number}"), Some(Target::Number(number))),
+                (format!("https://github.com/{owner}/{name}/pull/{number}"), Some(Target::Pull(number))),
+                (format!("https://github.com/{owner}/{name}/issues/{number}"), Some(Target::Issue(number))),
+                (format!("https://github.com/{owner}/{name}.git"), None),
+                (format!("git@github.com:{owner}/{name}.git"), None),
+            ];
+            for (input, target) in forms {
+                prop_assert_eq!(
+                    input.parse::<Reference>(),
+                    Ok(reference("github.com", &owner, &name, target))
+                );
+            }
+        }
+
+        #[test]
+        fn prop_extra_segments(owner in owner(), name in name(), extra in "[a-z]{1,10}") {
+            let input = format!("{owner}/{name}/{extra}");
+            prop_assert!(input.parse::<FullName>().is_err());
+        }
+
+        #[test]
+        fn prop_long_owner(owner in "[a-z]{40,60}", name in name()) {
+            let input = format!("{owner}/{name}");
+            prop_assert!(input.parse::<FullName>().is_err());
+        }
+
+        #[test]
+        fn prop_no_panic(s in "\\PC*") {
+            let _ = s.parse::<Reference>();
+        }
+    }
+}
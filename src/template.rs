@@ -0,0 +1,105 @@
+use chrono::{Days, Months, NaiveDate};
+use eyre::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static RELATIVE_DATE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^today(?:(?<sign>[+-])(?<n>\d+)(?<unit>[dwmy]))?$").unwrap());
+
+/// Replace `{name}` placeholders with whatever `lookup` returns for `name`.
+///
+/// Placeholders that `lookup` doesn't know about are an error,
+/// a `{` without a closing `}` is left alone.
+pub fn render<F>(template: &str, mut lookup: F) -> Result<String>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        let value = lookup(name).ok_or_else(|| eyre::eyre!("unknown placeholder {{{name}}}"))?;
+        output.push_str(&rest[..start]);
+        output.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// `today`, `today-7d`, `today+2w`, `today-1m` or `today-1y` as a YYYY-MM-DD date.
+pub fn relative_date(name: &str, today: NaiveDate) -> Option<String> {
+    let caps = RELATIVE_DATE.captures(name)?;
+    let date = match (caps.name("sign"), caps.name("n"), caps.name("unit")) {
+        (Some(sign), Some(n), Some(unit)) => {
+            let n: u32 = n.as_str().parse().ok()?;
+            let days = Days::new(u64::from(n));
+            let weeks = Days::new(u64::from(n) * 7);
+            match (sign.as_str(), unit.as_str()) {
+                ("-", "d") => today.checked_sub_days(days),
+                ("+", "d") => today.checked_add_days(days),
+                ("-", "w") => today.checked_sub_days(weeks),
+                ("+", "w") => today.checked_add_days(weeks),
+                ("-", "m") => today.checked_sub_months(Months::new(n)),
+                ("+", "m") => today.checked_add_months(Months::new(n)),
+                ("-", _) => today.checked_sub_months(Months::new(n.checked_mul(12)?)),
+                _ => today.checked_add_months(Months::new(n.checked_mul(12)?)),
+            }
+        }
+        _ => Some(today),
+    }?;
+
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let lookup = |name: &str| match name {
+            "me" => Some("dylanwh".to_string()),
+            "input" => Some("".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            render("is:open author:{me} {input}", lookup).unwrap(),
+            "is:open author:dylanwh "
+        );
+        assert_eq!(
+            render("no placeholders", lookup).unwrap(),
+            "no placeholders"
+        );
+        assert_eq!(render("unclosed {me", lookup).unwrap(), "unclosed {me");
+        assert!(render("{nope}", lookup).is_err());
+    }
+
+    #[test]
+    fn test_relative_date() {
+        let today = NaiveDate::from_ymd_opt(2023, 3, 31).unwrap();
+        assert_eq!(relative_date("today", today).as_deref(), Some("2023-03-31"));
+        assert_eq!(
+            relative_date("today-7d", today).as_deref(),
+            Some("2023-03-24")
+        );
+        assert_eq!(
+            relative_date("today+2w", today).as_deref(),
+            Some("2023-04-14")
+        );
+        assert_eq!(
+            relative_date("today-1m", today).as_deref(),
+            Some("2023-02-28")
+        );
+        assert_eq!(
+            relative_date("today-1y", today).as_deref(),
+            Some("2022-03-31")
+        );
+        assert_eq!(relative_date("today-7", today), None);
+        assert_eq!(relative_date("yesterday", today), None);
+    }
+}